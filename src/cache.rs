@@ -0,0 +1,183 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use bytes::Bytes;
+
+use crate::{Errors, APP_NAME};
+
+const DDRAGON_URL: &str = "https://ddragon.leagueoflegends.com";
+
+// versions.json is the only asset that isn't keyed by a version, so it gets re-checked now and then
+const VERSIONS_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// How many of the newest patches are always kept on disk
+///
+/// Matches are shown with the assets of the patch they were played on, so every patch of the
+/// current season is kept as well, anything else is removed on startup
+const RETAINED_VERSIONS: usize = 3;
+
+static CACHE_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// The root of the asset cache, this is `None` if the platform has no data directory
+fn cache_dir() -> Option<&'static Path> {
+    CACHE_DIR
+        .get_or_init(|| eframe::storage_dir(APP_NAME).map(|dir| dir.join("ddragon")))
+        .as_deref()
+}
+
+fn asset_path(version: &str, path: &str) -> Option<PathBuf> {
+    cache_dir().map(|dir| dir.join(version).join(path))
+}
+
+async fn store(file: &Path, bytes: &[u8]) {
+    if let Some(parent) = file.parent() {
+        if let Err(err) = tokio::fs::create_dir_all(parent).await {
+            println!("Failed to create cache dir {:?}: {}", parent, err);
+            return;
+        }
+    }
+
+    // Written to a temp file first, so a half written asset is never read back
+    let part = file.with_extension("part");
+    let res = match tokio::fs::write(&part, bytes).await {
+        Ok(_) => tokio::fs::rename(&part, file).await,
+        Err(err) => Err(err),
+    };
+
+    if let Err(err) = res {
+        println!("Failed to cache {:?}: {}", file, err);
+    }
+}
+
+/// Fetches `{version}/{path}` from the data dragon CDN, reading it from disk if it was already downloaded
+pub async fn get_asset(
+    version: &str,
+    path: &str,
+    client: &reqwest::Client,
+) -> Result<Bytes, reqwest::Error> {
+    let file = asset_path(version, path);
+
+    if let Some(file) = &file {
+        if let Ok(bytes) = tokio::fs::read(file).await {
+            return Ok(bytes.into());
+        }
+    }
+
+    let bytes = client
+        .get(format!("{DDRAGON_URL}/cdn/{version}/{path}"))
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    if let Some(file) = &file {
+        store(file, &bytes).await;
+    }
+
+    Ok(bytes)
+}
+
+/// Removes a cached asset, used when the bytes on disk turn out to be unusable
+pub async fn evict(version: &str, path: &str) {
+    if let Some(file) = asset_path(version, path) {
+        let _ = tokio::fs::remove_file(file).await;
+    }
+}
+
+/// Fetches the list of data dragon versions, newest first
+///
+/// A cached copy is used if it is recent, or if the CDN can't be reached
+pub async fn get_versions(client: &reqwest::Client) -> Result<Box<[String]>, Errors> {
+    versions(client, VERSIONS_MAX_AGE).await
+}
+
+/// Same as [`get_versions`], but always checks the CDN for a new patch first
+pub async fn refresh_versions(client: &reqwest::Client) -> Result<Box<[String]>, Errors> {
+    versions(client, Duration::ZERO).await
+}
+
+async fn versions(client: &reqwest::Client, max_age: Duration) -> Result<Box<[String]>, Errors> {
+    let file = cache_dir().map(|dir| dir.join("versions.json"));
+
+    let cached = match &file {
+        Some(file) => read_versions(file).await,
+        None => None,
+    };
+
+    if let Some((versions, age)) = &cached {
+        if *age < max_age {
+            return Ok(versions.clone());
+        }
+    }
+
+    let res = fetch_versions(client).await;
+
+    match (res, cached) {
+        (Ok((versions, bytes)), _) => {
+            if let Some(file) = &file {
+                store(file, &bytes).await;
+            }
+            Ok(versions)
+        }
+        (Err(err), Some((versions, _))) => {
+            println!("Using cached versions.json: {}", err);
+            Ok(versions)
+        }
+        (Err(err), None) => Err(err),
+    }
+}
+
+async fn fetch_versions(client: &reqwest::Client) -> Result<(Box<[String]>, Bytes), Errors> {
+    let bytes = client
+        .get(format!("{DDRAGON_URL}/api/versions.json"))
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    Ok((serde_json::from_slice(&bytes)?, bytes))
+}
+
+async fn read_versions(file: &Path) -> Option<(Box<[String]>, Duration)> {
+    let age = tokio::fs::metadata(file)
+        .await
+        .ok()?
+        .modified()
+        .ok()?
+        .elapsed()
+        .unwrap_or_default();
+    let bytes = tokio::fs::read(file).await.ok()?;
+    let versions = serde_json::from_slice(&bytes).ok()?;
+    Some((versions, age))
+}
+
+/// Deletes the cached assets of every patch except the newest few, and those of the current season
+pub async fn prune(versions: &[String]) {
+    let Some(dir) = cache_dir() else {
+        return;
+    };
+
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return;
+    };
+
+    let retained = &versions[..versions.len().min(RETAINED_VERSIONS)];
+    let season = |version: &str| version.split('.').next().map(str::to_owned);
+    let current_season = versions.first().and_then(|version| season(version));
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let is_dir = entry.file_type().await.is_ok_and(|kind| kind.is_dir());
+        let name = entry.file_name();
+        let name_str = name.to_string_lossy();
+        let keep = retained.iter().any(|version| name == version.as_str())
+            || (current_season.is_some() && season(&name_str) == current_season);
+        if is_dir && !keep {
+            if let Err(err) = tokio::fs::remove_dir_all(entry.path()).await {
+                println!("Failed to remove old assets {:?}: {}", entry.path(), err);
+            }
+        }
+    }
+}
@@ -0,0 +1,706 @@
+use std::cmp::Ordering;
+
+use crate::stats;
+use crate::structs::{MatchStats, MatchSummary, Team};
+
+/// Matches shorter than this are remakes, in seconds
+pub const REMAKE_DURATION: i64 = 5 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchSort {
+    Date,
+    Kda,
+    Damage,
+    Duration,
+    HardCarry,
+    Teamplay,
+}
+
+impl MatchSort {
+    pub const ALL: [(MatchSort, &'static str); 6] = [
+        (MatchSort::Date, "Date"),
+        (MatchSort::Kda, "KDA"),
+        (MatchSort::Damage, "Damage"),
+        (MatchSort::Duration, "Duration"),
+        (MatchSort::HardCarry, "Hard Carry"),
+        (MatchSort::Teamplay, "Teamplay"),
+    ];
+
+    fn compare(self, a: &MatchSummary, b: &MatchSummary) -> Ordering {
+        match self {
+            MatchSort::Date => a.match_creation_time.cmp(&b.match_creation_time),
+            MatchSort::Kda => a.kda().total_cmp(&b.kda()),
+            MatchSort::Damage => a.damage.cmp(&b.damage),
+            MatchSort::Duration => a.match_duration.cmp(&b.match_duration),
+            MatchSort::HardCarry => a.ps_hard_carry.cmp(&b.ps_hard_carry),
+            MatchSort::Teamplay => a.ps_team_play.cmp(&b.ps_team_play),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    All,
+    Wins,
+    Losses,
+}
+
+/// How the loaded matches are sorted and filtered, none of this is sent to u.gg
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchListOptions {
+    pub sort: MatchSort,
+    pub descending: bool,
+    pub outcome: Outcome,
+    pub hide_remakes: bool,
+    /// Only matches in this queue are shown, every queue if it's `None`
+    pub queue: Option<String>,
+    pub query: Option<Query>,
+}
+
+impl Default for MatchListOptions {
+    fn default() -> Self {
+        Self {
+            sort: MatchSort::Date,
+            descending: true,
+            outcome: Outcome::All,
+            hide_remakes: false,
+            queue: None,
+            query: None,
+        }
+    }
+}
+
+impl MatchListOptions {
+    pub fn matches(&self, summary: &MatchSummary) -> bool {
+        let outcome = match self.outcome {
+            Outcome::All => true,
+            Outcome::Wins => summary.win,
+            Outcome::Losses => !summary.win,
+        };
+
+        outcome
+            && !(self.hide_remakes && is_remake(summary))
+            && self
+                .queue
+                .as_ref()
+                .is_none_or(|queue| *queue == summary.queue_type)
+            && self.query.as_ref().is_none_or(|query| query.matches(summary))
+    }
+
+    /// The matches that pass the filters, in the chosen order
+    pub fn apply<'a>(&self, summaries: &'a [MatchSummary]) -> Vec<&'a MatchSummary> {
+        let mut shown: Vec<_> = summaries
+            .iter()
+            .filter(|summary| self.matches(summary))
+            .collect();
+
+        // The sort is stable, so ties stay newest first
+        shown.sort_by(|a, b| {
+            let order = self.sort.compare(a, b);
+            if self.descending {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+        shown
+    }
+}
+
+pub fn is_remake(summary: &MatchSummary) -> bool {
+    summary.match_duration < REMAKE_DURATION
+}
+
+/// Every queue in the matches, in the order they first show up
+pub fn queues(summaries: &[MatchSummary]) -> Vec<&str> {
+    let mut queues: Vec<&str> = vec![];
+    for summary in summaries {
+        if !queues.contains(&summary.queue_type.as_str()) {
+            queues.push(&summary.queue_type);
+        }
+    }
+    queues
+}
+
+/// A readable name for u.gg's queue ids, anything unknown is shown as is
+pub fn queue_name(queue: &str) -> &str {
+    match queue {
+        "ranked_solo_5x5" => "Ranked Solo",
+        "ranked_flex_sr" => "Ranked Flex",
+        "normal_draft_5x5" => "Normal Draft",
+        "normal_blind_5x5" => "Normal Blind",
+        "quickplay_5x5" => "Quickplay",
+        "aram" => "ARAM",
+        "arena" => "Arena",
+        queue => queue,
+    }
+}
+
+/// A parsed match filter, every term has to match for a match to be shown
+///
+/// Terms are separated by spaces, and a leading `-` negates one:
+/// `champ:Ahri role:mid win kda>3 patch:14.5 with:Name#TAG -queue:aram`
+///
+/// Values with spaces in them are quoted, like `with:"Hide on bush#KR1"`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    negated: bool,
+    condition: Condition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    Champion(i64),
+    Role(i64),
+    Win(bool),
+    Remake,
+    Queue(String),
+    Patch((u32, u32)),
+    /// A Riot ID on the player's team, or on the other one
+    With(String, String),
+    Against(String, String),
+    Compare(Stat, Comparison, f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stat {
+    Kda,
+    Kills,
+    Deaths,
+    Assists,
+    Cs,
+    CsPerMin,
+    Damage,
+    Gold,
+    Vision,
+    KillParticipation,
+    /// In minutes
+    Duration,
+}
+
+impl Stat {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "kda" => Stat::Kda,
+            "kills" | "k" => Stat::Kills,
+            "deaths" | "d" => Stat::Deaths,
+            "assists" | "a" => Stat::Assists,
+            "cs" => Stat::Cs,
+            "cspm" | "cs/min" => Stat::CsPerMin,
+            "damage" | "dmg" => Stat::Damage,
+            "gold" => Stat::Gold,
+            "vision" => Stat::Vision,
+            "kp" => Stat::KillParticipation,
+            "duration" | "length" => Stat::Duration,
+            _ => return None,
+        })
+    }
+
+    fn value(self, summary: &MatchSummary) -> f64 {
+        match self {
+            Stat::Kda => summary.kda(),
+            Stat::Kills => summary.kills as f64,
+            Stat::Deaths => summary.deaths as f64,
+            Stat::Assists => summary.assists as f64,
+            Stat::Cs => summary.total_cs() as f64,
+            Stat::CsPerMin => summary.cs_per_min(),
+            Stat::Damage => summary.damage as f64,
+            Stat::Gold => summary.gold as f64,
+            Stat::Vision => summary.vision_score as f64,
+            Stat::KillParticipation => summary.kill_participation as f64,
+            Stat::Duration => summary.match_duration as f64 / 60.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Less,
+    LessEqual,
+    Equal,
+    GreaterEqual,
+    Greater,
+}
+
+impl Comparison {
+    const OPERATORS: [(&'static str, Comparison); 5] = [
+        ("<=", Comparison::LessEqual),
+        (">=", Comparison::GreaterEqual),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+        ("=", Comparison::Equal),
+    ];
+
+    fn compare(self, value: f64, target: f64) -> bool {
+        match self {
+            Comparison::Less => value < target,
+            Comparison::LessEqual => value <= target,
+            Comparison::Equal => value == target,
+            Comparison::GreaterEqual => value >= target,
+            Comparison::Greater => value > target,
+        }
+    }
+}
+
+/// Why a query couldn't be parsed, and where in it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    /// The byte range of the term that failed
+    pub span: std::ops::Range<usize>,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (at {})", self.message, self.span.start)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl Query {
+    /// Parses a query, `champion_id` looks up champion names since the matches only have ids
+    ///
+    /// Champions can also be given by their id, e.g. `champ:103`
+    pub fn parse(input: &str, champion_id: impl Fn(&str) -> Option<i64>) -> Result<Self, ParseError> {
+        let mut terms = vec![];
+
+        for (span, token) in tokenize(input)? {
+            let error = |message: String| ParseError {
+                message,
+                span: span.clone(),
+            };
+
+            let (negated, term) = match token.strip_prefix('-') {
+                Some(term) => (true, term),
+                None => (false, token.as_str()),
+            };
+
+            let condition = match term.split_once(':') {
+                Some((key, value)) => {
+                    if value.is_empty() {
+                        return Err(error(format!("'{}' is missing a value", token)));
+                    }
+                    parse_filter(&key.to_lowercase(), value, &champion_id).map_err(error)?
+                }
+                None => parse_word(term).map_err(error)?,
+            };
+
+            terms.push(Term { negated, condition });
+        }
+
+        Ok(Self { terms })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, summary: &MatchSummary) -> bool {
+        self.terms
+            .iter()
+            .all(|term| term.condition.matches(summary) != term.negated)
+    }
+
+    /// The matches the query applies to, in the order they were given
+    pub fn filter<'a>(
+        &'a self,
+        summaries: &'a [MatchSummary],
+    ) -> impl Iterator<Item = &'a MatchSummary> + 'a {
+        summaries.iter().filter(|summary| self.matches(summary))
+    }
+}
+
+impl Condition {
+    fn matches(&self, summary: &MatchSummary) -> bool {
+        match self {
+            Condition::Champion(id) => summary.champion_id == *id,
+            Condition::Role(role) => summary.role == *role,
+            Condition::Win(win) => summary.win == *win,
+            Condition::Remake => is_remake(summary),
+            Condition::Queue(queue) => {
+                summary.queue_type.eq_ignore_ascii_case(queue)
+                    || queue_name(&summary.queue_type).eq_ignore_ascii_case(queue)
+            }
+            Condition::Patch(patch) => stats::patch(&summary.version) == Some(*patch),
+            Condition::With(name, tag_line) => stats::teams(summary)
+                .is_some_and(|(team, _)| has_player(team, name, tag_line)),
+            Condition::Against(name, tag_line) => stats::teams(summary)
+                .is_some_and(|(_, opponents)| has_player(opponents, name, tag_line)),
+            Condition::Compare(stat, comparison, target) => {
+                comparison.compare(stat.value(summary), *target)
+            }
+        }
+    }
+}
+
+/// Splits a query on whitespace that isn't quoted, the quotes themselves are left out
+///
+/// Each term comes with the byte range it was in, quotes included
+fn tokenize(input: &str) -> Result<Vec<(std::ops::Range<usize>, String)>, ParseError> {
+    let mut tokens = vec![];
+    let mut current: Option<(usize, String)> = None;
+    let mut open_quote = None;
+
+    for (index, c) in input.char_indices() {
+        if c == '"' {
+            open_quote = match open_quote {
+                Some(_) => None,
+                None => Some(index),
+            };
+            current.get_or_insert_with(|| (index, String::new()));
+        } else if c.is_whitespace() && open_quote.is_none() {
+            if let Some((start, token)) = current.take() {
+                tokens.push((start..index, token));
+            }
+        } else {
+            current.get_or_insert_with(|| (index, String::new())).1.push(c);
+        }
+    }
+
+    if let Some(start) = open_quote {
+        return Err(ParseError {
+            message: "This quote is never closed".to_owned(),
+            span: start..input.len(),
+        });
+    }
+    if let Some((start, token)) = current {
+        tokens.push((start..input.len(), token));
+    }
+
+    Ok(tokens)
+}
+
+fn has_player(team: &[Team], name: &str, tag_line: &str) -> bool {
+    team.iter().any(|player| {
+        player.riot_user_name.eq_ignore_ascii_case(name)
+            && player.riot_tag_line.eq_ignore_ascii_case(tag_line)
+    })
+}
+
+fn parse_filter(
+    key: &str,
+    value: &str,
+    champion_id: &impl Fn(&str) -> Option<i64>,
+) -> Result<Condition, String> {
+    match key {
+        "champ" | "champion" => value
+            .parse()
+            .ok()
+            .or_else(|| champion_id(value))
+            .map(Condition::Champion)
+            .ok_or_else(|| format!("There is no champion called '{}'", value)),
+        "role" => role_id(value)
+            .map(Condition::Role)
+            .ok_or_else(|| format!("'{}' isn't a role, try top, jungle, mid, adc or support", value)),
+        "queue" => Ok(Condition::Queue(value.to_owned())),
+        "patch" => stats::patch(value)
+            .map(Condition::Patch)
+            .ok_or_else(|| format!("'{}' isn't a patch, it should look like 14.5", value)),
+        "with" | "vs" => {
+            let (name, tag_line) = value
+                .split_once('#')
+                .filter(|(name, tag_line)| !name.is_empty() && !tag_line.is_empty())
+                .ok_or_else(|| format!("'{}' should be a Riot ID like Name#TAG", value))?;
+            let (name, tag_line) = (name.to_owned(), tag_line.to_owned());
+            Ok(if key == "with" {
+                Condition::With(name, tag_line)
+            } else {
+                Condition::Against(name, tag_line)
+            })
+        }
+        _ => Err(format!(
+            "Unknown filter '{}', try champ, role, queue, patch, with or vs",
+            key
+        )),
+    }
+}
+
+fn parse_word(word: &str) -> Result<Condition, String> {
+    match word.to_lowercase().as_str() {
+        "win" | "wins" | "victory" => return Ok(Condition::Win(true)),
+        "loss" | "losses" | "lose" | "defeat" => return Ok(Condition::Win(false)),
+        "remake" | "remakes" => return Ok(Condition::Remake),
+        _ => {}
+    }
+
+    let Some((index, operator, comparison)) = Comparison::OPERATORS
+        .iter()
+        .filter_map(|(operator, comparison)| {
+            word.find(operator).map(|index| (index, *operator, *comparison))
+        })
+        // The first operator in the word, and the longest one there, so `>=` isn't read as `>`
+        .min_by_key(|(index, operator, _)| (*index, std::cmp::Reverse(operator.len())))
+    else {
+        return Err(format!("Unknown filter '{}'", word));
+    };
+
+    let (stat, value) = (&word[..index], &word[index + operator.len()..]);
+    let stat = Stat::from_name(&stat.to_lowercase())
+        .ok_or_else(|| format!("'{}' can't be compared, try kda, kills, cs or damage", stat))?;
+    let value = value
+        .parse()
+        .map_err(|_| format!("'{}' isn't a number", value))?;
+
+    Ok(Condition::Compare(stat, comparison, value))
+}
+
+/// u.gg's role ids, by the names players use for them
+fn role_id(role: &str) -> Option<i64> {
+    Some(match role.to_lowercase().as_str() {
+        "jungle" | "jg" | "jgl" => 1,
+        "support" | "supp" | "sup" => 2,
+        "adc" | "bot" | "carry" => 3,
+        "top" => 4,
+        "mid" | "middle" => 5,
+        _ => return None,
+    })
+}
+
+/// Champion names without case, spaces or punctuation, so `kaisa` finds Kai'Sa
+pub fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn champion_id(name: &str) -> Option<i64> {
+        match normalize_name(name).as_str() {
+            "ahri" => Some(103),
+            "leesin" => Some(64),
+            _ => None,
+        }
+    }
+
+    fn parse(input: &str) -> Result<Query, ParseError> {
+        Query::parse(input, champion_id)
+    }
+
+    fn term(negated: bool, condition: Condition) -> Term {
+        Term { negated, condition }
+    }
+
+    /// A ranked match with only the searched player in it
+    fn summary(match_id: i64, match_duration: i64, win: bool) -> MatchSummary {
+        MatchSummary {
+            assists: 0,
+            champion_id: 103,
+            cs: 0,
+            damage: 0,
+            deaths: 0,
+            gold: 0,
+            items: vec![],
+            jungle_cs: 0,
+            kill_participation: 0,
+            kills: 0,
+            level: 18,
+            lp_info: None,
+            match_creation_time: match_id,
+            match_duration,
+            match_id,
+            maximum_kill_streak: 0,
+            primary_style: 0,
+            ps_hard_carry: 0,
+            ps_team_play: 0,
+            queue_type: "ranked_solo_5x5".to_owned(),
+            region_id: "euw1".to_owned(),
+            role: 3,
+            runes: vec![],
+            sub_style: 0,
+            riot_user_name: "Searched".to_owned(),
+            riot_tag_line: "EUW".to_owned(),
+            summoner_spells: vec![],
+            team_a: vec![],
+            team_b: vec![],
+            version: "14.5.1".to_owned(),
+            vision_score: 0,
+            win,
+        }
+    }
+
+    fn shown(options: &MatchListOptions, summaries: &[MatchSummary]) -> Vec<i64> {
+        options
+            .apply(summaries)
+            .iter()
+            .map(|summary| summary.match_id)
+            .collect()
+    }
+
+    #[test]
+    fn parses_every_operator() {
+        let query = parse("kda<3 kills<=10 deaths=2 cs>=200 dmg>30000").unwrap();
+
+        assert_eq!(
+            query.terms,
+            vec![
+                term(false, Condition::Compare(Stat::Kda, Comparison::Less, 3.0)),
+                term(false, Condition::Compare(Stat::Kills, Comparison::LessEqual, 10.0)),
+                term(false, Condition::Compare(Stat::Deaths, Comparison::Equal, 2.0)),
+                term(false, Condition::Compare(Stat::Cs, Comparison::GreaterEqual, 200.0)),
+                term(false, Condition::Compare(Stat::Damage, Comparison::Greater, 30000.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn compares_on_the_boundary() {
+        assert!(!Comparison::Less.compare(3.0, 3.0));
+        assert!(Comparison::LessEqual.compare(3.0, 3.0));
+        assert!(Comparison::Equal.compare(3.0, 3.0));
+        assert!(Comparison::GreaterEqual.compare(3.0, 3.0));
+        assert!(!Comparison::Greater.compare(3.0, 3.0));
+    }
+
+    #[test]
+    fn parses_filters_and_negation() {
+        let query = parse("champ:Ahri -role:jg win -remake patch:14_5 -queue:aram").unwrap();
+
+        assert_eq!(
+            query.terms,
+            vec![
+                term(false, Condition::Champion(103)),
+                term(true, Condition::Role(1)),
+                term(false, Condition::Win(true)),
+                term(true, Condition::Remake),
+                term(false, Condition::Patch((14, 5))),
+                term(true, Condition::Queue("aram".to_owned())),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_quoted_values() {
+        let query = parse(r#"with:"Hide on bush#KR1" -vs:"Some One#NA1" champ:"Lee Sin""#).unwrap();
+
+        assert_eq!(
+            query.terms,
+            vec![
+                term(
+                    false,
+                    Condition::With("Hide on bush".to_owned(), "KR1".to_owned())
+                ),
+                term(true, Condition::Against("Some One".to_owned(), "NA1".to_owned())),
+                term(false, Condition::Champion(64)),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_champions() {
+        let err = parse("lane:mid").unwrap_err();
+        assert!(err.message.starts_with("Unknown filter 'lane'"));
+
+        let err = parse("champ:Nobody").unwrap_err();
+        assert_eq!(err.message, "There is no champion called 'Nobody'");
+
+        let err = parse("speed>3").unwrap_err();
+        assert!(err.message.starts_with("'speed' can't be compared"));
+    }
+
+    #[test]
+    fn error_spans_cover_the_failed_term() {
+        let input = r#"win  with:"Hide on bush" kda>3"#;
+        let err = parse(input).unwrap_err();
+        assert_eq!(&input[err.span], r#"with:"Hide on bush""#);
+
+        let input = r#"win with:"Hide on bush#KR1"#;
+        let err = parse(input).unwrap_err();
+        assert_eq!(&input[err.span], r#""Hide on bush#KR1"#);
+
+        let err = parse("-champ: win").unwrap_err();
+        assert_eq!(err.span, 0..7);
+    }
+
+    #[test]
+    fn empty_queries_have_no_terms() {
+        assert!(parse("").unwrap().is_empty());
+        assert!(parse("   ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn remakes_are_shorter_than_the_remake_duration() {
+        let summaries = [
+            summary(1, REMAKE_DURATION - 1, false),
+            summary(2, REMAKE_DURATION, true),
+            summary(3, 0, true),
+        ];
+        let mut options = MatchListOptions::default();
+        assert_eq!(shown(&options, &summaries), vec![3, 2, 1]);
+
+        options.hide_remakes = true;
+        assert_eq!(shown(&options, &summaries), vec![2]);
+    }
+
+    #[test]
+    fn sorts_keep_ties_in_their_order() {
+        let scored = |match_id, kills, deaths, damage| MatchSummary {
+            kills,
+            deaths,
+            damage,
+            ..summary(match_id, 30 * 60, true)
+        };
+        // A deathless game's KDA is its kills and assists, and 4 and 3 have the same damage
+        let summaries = [
+            scored(4, 6, 0, 20000),
+            scored(3, 10, 2, 20000),
+            scored(2, 3, 1, 35000),
+            scored(1, 0, 5, 0),
+        ];
+        let mut options = MatchListOptions {
+            sort: MatchSort::Damage,
+            ..MatchListOptions::default()
+        };
+        assert_eq!(shown(&options, &summaries), vec![2, 4, 3, 1]);
+
+        options.descending = false;
+        assert_eq!(shown(&options, &summaries), vec![1, 4, 3, 2]);
+
+        options.sort = MatchSort::Kda;
+        assert_eq!(shown(&options, &summaries), vec![1, 2, 3, 4]);
+
+        options.sort = MatchSort::Date;
+        assert_eq!(shown(&options, &summaries), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn every_filter_has_to_match() {
+        let queued = |match_id, queue_type: &str, win, kills| MatchSummary {
+            queue_type: queue_type.to_owned(),
+            kills,
+            ..summary(match_id, 20 * 60, win)
+        };
+        let summaries = [
+            queued(5, "aram", false, 12),
+            queued(4, "aram", true, 12),
+            queued(3, "aram", false, 2),
+            queued(2, "ranked_solo_5x5", false, 12),
+            queued(1, "Aram", false, 12),
+        ];
+        let mut options = MatchListOptions {
+            outcome: Outcome::Losses,
+            ..MatchListOptions::default()
+        };
+        assert_eq!(shown(&options, &summaries), vec![5, 3, 2, 1]);
+
+        // The queue has to be exactly the one that was picked
+        options.queue = Some("aram".to_owned());
+        assert_eq!(shown(&options, &summaries), vec![5, 3]);
+
+        options.query = Some(parse("kills>=10").unwrap());
+        assert_eq!(shown(&options, &summaries), vec![5]);
+
+        options.outcome = Outcome::Wins;
+        assert_eq!(shown(&options, &summaries), vec![4]);
+        assert!(shown(&options, &[]).is_empty());
+    }
+}
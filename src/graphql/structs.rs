@@ -30,14 +30,6 @@ pub struct UpdatePlayerProfile<'s> {
     pub riot_tag_line: &'s str,
 }
 
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct FetchProfileRanks {
-    pub region_id: &'static str,
-    pub summoner_name: Arc<String>,
-    pub season_id: i32,
-}
-
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetOverallPlayerRanking<'s> {
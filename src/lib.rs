@@ -0,0 +1,5 @@
+//! The parts of the app that don't need the GUI, u.gg's responses and what is worked out from them
+
+pub mod filter;
+pub mod stats;
+pub mod structs;
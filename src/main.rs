@@ -2,32 +2,43 @@
 
 use std::{
     collections::BTreeMap,
-    sync::{OnceLock, RwLock},
+    sync::{atomic::Ordering, Arc, OnceLock, RwLock},
 };
 
 use async_channel::{Receiver, Sender};
 use bytes::Bytes;
 use eframe::{
     egui::TextureOptions,
-    epaint::{ColorImage, TextureHandle},
+    epaint::{Color32, ColorImage, TextureHandle},
 };
 use std::collections::HashMap;
 use std::fmt::Display;
-use structs::ChampionJson;
+use serde::de::DeserializeOwned;
+use structs::{ChampionJson, RuneTree, SummonerJson};
 use tokio::runtime::Runtime;
+use ugg_match_api::{filter, stats, structs};
 use ui::{Champ, Payload, Results};
 
+mod cache;
 mod graphql;
 #[path = "networking/networking.rs"]
 mod networking;
-mod structs;
 mod ui;
 mod ui_logic;
 
+const APP_NAME: &str = "UGG API TEST";
+
+// Every page is its own request, so the stats over all pages stop somewhere
+const ALL_PAGES_LIMIT: u8 = 10;
+
 fn main() {
-    let native_options = eframe::NativeOptions::default();
+    // The window's size and position are saved alongside the app's settings
+    let native_options = eframe::NativeOptions {
+        persist_window: true,
+        ..Default::default()
+    };
     let _ = eframe::run_native(
-        "UGG API TEST",
+        APP_NAME,
         native_options,
         Box::new(|cc| Box::new(ui::MyEguiApp::new(cc))),
     );
@@ -36,29 +47,95 @@ fn main() {
 impl SharedState {
     const fn new() -> Self {
         Self {
-            champs: OnceLock::new(),
-            versions: OnceLock::new(),
+            champs: RwLock::new(None),
+            versions: RwLock::new(None),
             player_icons: RwLock::new(BTreeMap::new()),
+            item_icons: RwLock::new(BTreeMap::new()),
+            summoner_spells: OnceLock::new(),
+            runes: OnceLock::new(),
+            placeholder: OnceLock::new(),
+        }
+    }
+
+    /// Shown in place of any portrait or icon that failed to load
+    fn placeholder(&self, ctx: &eframe::egui::Context) -> TextureHandle {
+        self.placeholder
+            .get_or_init(|| {
+                ctx.load_texture(
+                    "placeholder",
+                    ColorImage::new([120, 120], Color32::DARK_GRAY),
+                    TextureOptions::LINEAR,
+                )
+            })
+            .clone()
+    }
+
+    fn champs(&self) -> Option<Arc<HashMap<i64, Champ>>> {
+        self.champs.read().unwrap().clone()
+    }
+
+    fn versions(&self) -> Option<Arc<[String]>> {
+        self.versions.read().unwrap().clone()
+    }
+
+    /// Swaps in the versions and champ data from a newer patch, keeping any portraits that were already loaded
+    fn replace_champs(&self, versions: Box<[String]>, mut champs: HashMap<i64, Champ>) {
+        // Both are locked together so the versions never point past the loaded champs
+        let mut versions_write = self.versions.write().unwrap();
+        let mut write = self.champs.write().unwrap();
+        if let Some(old) = write.as_ref() {
+            for (id, champ) in champs.iter_mut() {
+                if let Some(old) = old.get(id) {
+                    *champ.image.get_mut().unwrap() = old.image.read().unwrap().clone();
+                    *champ.image_started.get_mut() = old.image_started.load(Ordering::Relaxed);
+                }
+            }
         }
+        *write = Some(Arc::new(champs));
+        *versions_write = Some(versions.into());
     }
 
     fn update_champ_image(&self, champ_id: i64, texture: TextureHandle) {
-        let map = self.champs.get().unwrap();
+        let map = self.champs().unwrap();
         let handle = map
             .get(&champ_id)
             .expect("The map is already loaded by now");
         let mut write = handle.image.write().unwrap();
         *write = Some(texture);
     }
+
+    fn update_item_image(&self, version: &str, item_id: i64, texture: TextureHandle) {
+        let mut map = self.item_icons.write().unwrap();
+        map.entry(version.to_owned())
+            .or_default()
+            .insert(item_id, Some(texture));
+    }
+
+    /// Sprites are lower resolution, so they never replace a portrait that is already loaded
+    fn update_champ_sprite(&self, champ: &Champ, texture: TextureHandle) {
+        let mut write = champ.image.write().unwrap();
+        if write.is_none() {
+            *write = Some(texture);
+        }
+    }
 }
 
 static SHARED_STATE: SharedState = SharedState::new();
 
 pub struct SharedState {
     // This is initialized once, and because of the way the GUI is set up, will always be there afterward
-    champs: OnceLock<HashMap<i64, Champ>>,
-    versions: OnceLock<Box<[String]>>,
+    // It is only ever replaced when a match has a champ that is newer than the loaded data
+    champs: RwLock<Option<Arc<HashMap<i64, Champ>>>>,
+    // Replaced along with the champs, so the latest version is always the one they were loaded from
+    versions: RwLock<Option<Arc<[String]>>>,
     player_icons: RwLock<BTreeMap<i16, TextureHandle>>,
+    // Items change between patches, so these are keyed by the data dragon version first
+    // None means the image has been requested, but isn't loaded yet
+    item_icons: RwLock<BTreeMap<String, BTreeMap<i64, Option<TextureHandle>>>>,
+    // Names of summoner spells, runes and rune trees by their id
+    summoner_spells: OnceLock<HashMap<i64, String>>,
+    runes: OnceLock<HashMap<i64, String>>,
+    placeholder: OnceLock<TextureHandle>,
 }
 
 struct ThreadState {
@@ -93,20 +170,6 @@ impl ThreadState {
     }
 }
 
-async fn try_message_sender<T>(
-    result: Result<T, Results>,
-    ctx: &eframe::egui::Context,
-    thread_sender: &Sender<Results>,
-) -> Option<T> {
-    match result {
-        Ok(t) => Some(t),
-        Err(e) => {
-            message_sender(e, ctx, thread_sender).await;
-            None
-        }
-    }
-}
-
 async fn message_sender(
     message: Results,
     ctx: &eframe::egui::Context,
@@ -141,9 +204,11 @@ pub fn spawn_gui_shit(
             while let Ok(message) = state.receiver().recv().await {
                 match message {
                     Payload::MatchSummaries {
+                        tab,
                         name,
                         tag_line,
                         roles,
+                        champion,
                         region_id,
                         page,
                     } => {
@@ -152,16 +217,54 @@ pub fn spawn_gui_shit(
                             &tag_line,
                             region_id,
                             roles.as_slice(),
+                            champion.as_slice(),
                             page,
                             state.client(),
                         )
                         .await
                         .map_err(Errors::Request);
 
-                        message_sender(Results::MatchSum(request), state.ctx(), state.sender())
+                        message_sender(Results::MatchSum(tab, request), state.ctx(), state.sender())
+                            .await;
+                    }
+                    Payload::AllMatchSummaries {
+                        tab,
+                        name,
+                        tag_line,
+                        roles,
+                        champion,
+                        region_id,
+                    } => {
+                        let res = async {
+                            let mut summaries = vec![];
+                            for page in 1..=ALL_PAGES_LIMIT {
+                                let request = networking::fetch_match_summaries(
+                                    &name,
+                                    &tag_line,
+                                    region_id,
+                                    roles.as_slice(),
+                                    champion.as_slice(),
+                                    page,
+                                    state.client(),
+                                )
+                                .await?;
+
+                                let page = request.data.fetch_player_match_summaries.match_summaries;
+                                let finished = page.len() != 20;
+                                summaries.extend(page.into_vec());
+                                if finished {
+                                    break;
+                                }
+                            }
+                            Ok(summaries.into_boxed_slice())
+                        }
+                        .await;
+
+                        message_sender(Results::AllMatchSum(tab, res), state.ctx(), state.sender())
                             .await;
                     }
                     Payload::UpdatePlayer {
+                        tab,
                         name,
                         tag_line,
                         region_id,
@@ -171,10 +274,11 @@ pub fn spawn_gui_shit(
                                 .await
                                 .map_err(Errors::Request);
 
-                        message_sender(Results::PlayerUpdate(request), state.ctx(), state.sender())
+                        message_sender(Results::PlayerUpdate(tab, request), state.ctx(), state.sender())
                             .await;
                     }
                     Payload::PlayerRanking {
+                        tab,
                         name,
                         tag_line,
                         region_id,
@@ -184,172 +288,175 @@ pub fn spawn_gui_shit(
                                 .await
                                 .map_err(Errors::Request);
 
-                        message_sender(Results::Ranking(request), state.ctx(), state.sender())
+                        message_sender(Results::Ranking(tab, request), state.ctx(), state.sender())
                             .await;
                     }
                     Payload::PlayerInfo {
+                        tab,
                         name,
                         tag_line,
                         version_index,
                         region_id,
                     } => {
-                        let val =
+                        let res =
                             networking::player_info(name, tag_line, region_id, state.client())
                                 .await
-                                .map_err(|e| Results::PlayerInfo(Err(e.into())));
+                                .map_err(Errors::Request);
 
-                        if let Some(info) = try_message_sender(val, state.ctx(), state.sender()).await {
+                        if let Ok(info) = &res {
                             if let Some(info) = &info.data.profile_init_simple {
-                                let res = get_icon(
-                                    info.player_info.icon_id,
-                                    &shared_state.versions.get().unwrap()[version_index],
-                                    state.client(),
-                                )
-                                .await
-                                .map_err(|e| Results::PlayerIcon(e.into()));
-
-                                if let Some(bytes) =
-                                    try_message_sender(res, state.ctx(), state.sender()).await
-                                {
-                                    let mut decoder = png::Decoder::new(&*bytes);
-                                    let headers = decoder.read_header_info().expect(
-                                        "This is always a PNG, so this shouldn't ever fail",
-                                    );
-
-                                    let x = headers.height as usize;
-                                    let y = headers.width as usize;
-
-                                    let mut reader = decoder.read_info().unwrap();
-
-                                    let mut buf = vec![0; reader.output_buffer_size()];
-
-                                    reader.next_frame(&mut buf).unwrap();
-
-                                    let texture = state.ctx().load_texture(
-                                        "icon",
-                                        ColorImage::from_rgb([x, y], &buf),
-                                        TextureOptions::LINEAR,
-                                    );
-                                    let mut map = shared_state.player_icons.write().unwrap();
-                                    map.insert(info.player_info.icon_id, texture);
-                                }
+                                let versions = shared_state.versions().unwrap();
+                                load_player_icon(info.player_info.icon_id, &versions[version_index]).await;
                             }
                         }
+
+                        message_sender(Results::PlayerInfo(tab, region_id, res), state.ctx(), state.sender())
+                            .await;
                     }
                     Payload::GetVersions => {
-                        let res = state
-                            .client()
-                            .get("https://ddragon.leagueoflegends.com/api/versions.json")
-                            .send()
-                            .await;
-
-                        let res = match res {
-                            Ok(val) => val.json().await,
-                            Err(err) => Err(err),
-                        };
-
-                        match res {
-                            Ok(json) => {
-                                shared_state.versions.get_or_init(|| json);
+                        match cache::get_versions(state.client()).await {
+                            Ok(versions) => {
+                                cache::prune(&versions).await;
+                                let mut write = shared_state.versions.write().unwrap();
+                                write.get_or_insert_with(|| versions.into());
                             }
                             Err(err) => {
-                                message_sender(
-                                    Results::Versions(Errors::Request(err)),
-                                    state.ctx(),
-                                    state.sender(),
-                                )
-                                .await;
+                                message_sender(Results::Versions(err), state.ctx(), state.sender())
+                                    .await;
                             }
                         };
                     }
-                    Payload::GetChampInfo { url } => {
-                        let res = state.client().get(url).send().await;
-
-                        let json = match res {
-                            Ok(res) => res.json().await,
+                    Payload::GetChampInfo { version, locale } => {
+                        let champs = match load_champs(&version, locale, state.client()).await {
+                            Ok(champs) => champs,
                             Err(err) => {
-                                message_sender(
-                                    Results::ChampJson(Errors::Request(err)),
-                                    state.ctx(),
-                                    state.sender(),
-                                )
-                                .await;
+                                message_sender(Results::ChampJson(err), state.ctx(), state.sender())
+                                    .await;
                                 continue;
                             }
                         };
-                        let json: ChampionJson = match json {
-                            Ok(json) => json,
+
+                        let mut write = shared_state.champs.write().unwrap();
+                        write.get_or_insert_with(|| Arc::new(champs));
+                    }
+                    Payload::RefreshChampInfo { locale } => {
+                        let versions = match cache::refresh_versions(state.client()).await {
+                            Ok(versions) => versions,
                             Err(err) => {
-                                message_sender(
-                                    Results::ChampJson(Errors::Request(err)),
-                                    state.ctx(),
-                                    state.sender(),
-                                )
-                                .await;
+                                message_sender(Results::ChampJson(err), state.ctx(), state.sender())
+                                    .await;
                                 continue;
                             }
                         };
 
-                        let mut champs: HashMap<i64, Champ> = HashMap::with_capacity(200);
-                        for (_, data) in json.data {
-                            let id: i64 = data.id.parse().unwrap();
-                            champs.insert(id, data.into());
-                        }
-                        shared_state.champs.get_or_init(|| champs);
+                        let loaded = shared_state.versions();
+                        let loaded = loaded.as_ref().and_then(|versions| versions.first());
+                        let Some(latest) = versions.first().filter(|latest| Some(*latest) != loaded)
+                        else {
+                            println!("There is no newer champion data than {:?}", loaded);
+                            continue;
+                        };
+
+                        let res = load_champs(latest, locale, state.client())
+                            .await
+                            .map(|champs| shared_state.replace_champs(versions, champs));
+
+                        message_sender(Results::ChampsRefreshed(res), state.ctx(), state.sender())
+                            .await;
                     }
-                    Payload::GetChampImage { url, id } => {
-                        // TODO: Check the returned data is a valid image
-                        let res = state.client().get(url).send().await;
-                        let res = match res {
-                            Ok(res) => res,
+                    Payload::GetStaticData { version, locale } => {
+                        let res = async {
+                            let spells = load_summoner_spells(&version, locale, state.client()).await?;
+                            let runes = load_runes(&version, locale, state.client()).await?;
+                            Ok((spells, runes))
+                        }
+                        .await;
+
+                        match res {
+                            Ok((spells, runes)) => {
+                                shared_state.summoner_spells.get_or_init(|| spells);
+                                shared_state.runes.get_or_init(|| runes);
+                                state.ctx().request_repaint();
+                            }
                             Err(err) => {
-                                message_sender(
-                                    Results::ChampImage(err.into()),
-                                    state.ctx(),
-                                    state.sender(),
-                                )
-                                .await;
-                                continue;
+                                message_sender(Results::StaticData(err), state.ctx(), state.sender())
+                                    .await;
+                            }
+                        }
+                    }
+                    Payload::GetChampImage { version, key, id } => {
+                        load_champ_image(&version, &key, id).await;
+                    }
+                    Payload::GetItemImage { version, id } => {
+                        let path = format!("img/item/{id}.png");
+                        let res = cache::get_asset(&version, &path, state.client())
+                            .await
+                            .map_err(Errors::Request)
+                            .and_then(|bytes| decode_image(&bytes).map_err(Errors::Image));
+
+                        let texture = match res {
+                            Ok(image) => {
+                                state
+                                    .ctx()
+                                    .load_texture("item", image, TextureOptions::LINEAR)
                             }
-                        };
-                        let bytes = &*match res.bytes().await {
-                            Ok(bytes) => bytes,
                             Err(err) => {
-                                message_sender(
-                                    Results::ChampImage(Errors::Request(err)),
-                                    state.ctx(),
-                                    state.sender(),
-                                )
-                                .await;
-                                continue;
+                                if let Errors::Image(_) = err {
+                                    cache::evict(&version, &path).await;
+                                }
+                                message_sender(Results::ItemImage(err), state.ctx(), state.sender())
+                                    .await;
+                                shared_state.placeholder(state.ctx())
                             }
                         };
 
-                        let mut decoder = png::Decoder::new(bytes);
-                        let headers = decoder
-                            .read_header_info()
-                            .map_err(|err| println!("{:?}", err))
-                            .expect("This is always a PNG, so this shouldn't ever fail");
-
-                        let x = headers.height as usize;
-                        let y = headers.width as usize;
+                        shared_state.update_item_image(&version, id, texture);
+                        state.ctx().request_repaint();
+                    }
+                    Payload::GetChampSprite { version, sheet } => {
+                        let path = format!("img/sprite/{sheet}");
+                        let res = cache::get_asset(&version, &path, state.client())
+                            .await
+                            .map_err(Errors::Request)
+                            .and_then(|bytes| decode_image(&bytes).map_err(Errors::Image));
 
-                        let mut reader = decoder.read_info().unwrap();
-                        let mut buf = vec![0; reader.output_buffer_size()];
+                        let champs = shared_state.champs().unwrap();
+                        let sprites = champs
+                            .iter()
+                            .filter(|(_, champ)| champ.version == version && champ.sprite.sheet == sheet);
 
-                        reader
-                            .next_frame(&mut buf)
-                            .expect("If the champ does not exist in the map, something is wrong");
+                        let image = match res {
+                            Ok(image) => image,
+                            Err(err) => {
+                                if let Errors::Image(_) = err {
+                                    cache::evict(&version, &path).await;
+                                }
+                                message_sender(Results::ChampImage(err), state.ctx(), state.sender())
+                                    .await;
+
+                                // The champs on this sheet would never load otherwise, so they get their own portraits
+                                for (id, champ) in sprites {
+                                    if !champ.image_started.swap(true, Ordering::Relaxed) {
+                                        load_champ_image(&champ.version, &champ.key, *id).await;
+                                    }
+                                }
+                                continue;
+                            }
+                        };
 
-                        let texture = state.ctx().load_texture(
-                            "icon",
-                            ColorImage::from_rgb([x, y], &buf),
-                            TextureOptions::LINEAR,
-                        );
+                        for (_, champ) in sprites {
+                            let texture = state.ctx().load_texture(
+                                &champ.key,
+                                image.region(&champ.sprite.rect, None),
+                                TextureOptions::LINEAR,
+                            );
+                            shared_state.update_champ_sprite(champ, texture);
+                        }
 
-                        shared_state.update_champ_image(id, texture);
+                        state.ctx().request_repaint();
                     }
                     Payload::GetMatchDetails {
+                        tab,
                         name,
                         tag_line,
                         id,
@@ -365,9 +472,12 @@ pub fn spawn_gui_shit(
                             state.client(),
                         )
                         .await
-                        .map(|json| (Box::new(json), id))
+                        .map(|mut json| {
+                            json.data.data_match.attach_post_game_data();
+                            (Box::new(json), id)
+                        })
                         .map_err(Errors::Request);
-                        message_sender(Results::MatchDetails(res), state.ctx(), state.sender())
+                        message_sender(Results::MatchDetails(tab, res), state.ctx(), state.sender())
                             .await;
                     }
                     Payload::GetPlayerSuggestions { name } => {
@@ -394,25 +504,155 @@ pub fn spawn_gui_shit(
     (runtime, gui_sender, gui_receiver)
 }
 
+async fn load_player_icon(icon_id: i16, version: &str) {
+    let state = &STATE;
+    let shared_state = &SHARED_STATE;
+
+    if shared_state.player_icons.read().unwrap().contains_key(&icon_id) {
+        return;
+    }
+
+    let res = get_icon(icon_id, version, state.client())
+        .await
+        .map_err(Errors::Request)
+        .and_then(|bytes| decode_image(&bytes).map_err(Errors::Image));
+
+    let texture = match res {
+        Ok(image) => state
+            .ctx()
+            .load_texture("icon", image, TextureOptions::LINEAR),
+        Err(err) => {
+            if let Errors::Image(_) = err {
+                cache::evict(version, &icon_path(icon_id)).await;
+            }
+            message_sender(Results::PlayerIcon(err), state.ctx(), state.sender()).await;
+            shared_state.placeholder(state.ctx())
+        }
+    };
+
+    let mut map = shared_state.player_icons.write().unwrap();
+    map.insert(icon_id, texture);
+}
+
+/// Loads a champ's portrait, the placeholder is shown instead if it can't be
+async fn load_champ_image(version: &str, key: &str, id: i64) {
+    let state = &STATE;
+    let shared_state = &SHARED_STATE;
+
+    let path = format!("img/champion/{key}.png");
+    let res = cache::get_asset(version, &path, state.client())
+        .await
+        .map_err(Errors::Request)
+        .and_then(|bytes| decode_image(&bytes).map_err(Errors::Image));
+
+    let texture = match res {
+        Ok(image) => state
+            .ctx()
+            .load_texture("icon", image, TextureOptions::LINEAR),
+        Err(err) => {
+            if let Errors::Image(_) = err {
+                cache::evict(version, &path).await;
+            }
+            message_sender(Results::ChampImage(err), state.ctx(), state.sender()).await;
+            shared_state.placeholder(state.ctx())
+        }
+    };
+
+    shared_state.update_champ_image(id, texture);
+}
+
+async fn load_champs(
+    version: &str,
+    locale: &str,
+    client: &reqwest::Client,
+) -> Result<HashMap<i64, Champ>, Errors> {
+    let path = format!("data/{locale}/champion.json");
+    let json: ChampionJson = load_json(version, &path, client).await?;
+
+    let mut champs: HashMap<i64, Champ> = HashMap::with_capacity(200);
+    for (_, data) in json.data {
+        let id: i64 = data.id.parse().unwrap();
+        champs.insert(id, data.into());
+    }
+
+    Ok(champs)
+}
+
+async fn load_json<T: DeserializeOwned>(
+    version: &str,
+    path: &str,
+    client: &reqwest::Client,
+) -> Result<T, Errors> {
+    let bytes = cache::get_asset(version, path, client).await?;
+    match serde_json::from_slice(&bytes) {
+        Ok(json) => Ok(json),
+        Err(err) => {
+            cache::evict(version, path).await;
+            Err(err.into())
+        }
+    }
+}
+
+async fn load_summoner_spells(
+    version: &str,
+    locale: &str,
+    client: &reqwest::Client,
+) -> Result<HashMap<i64, String>, Errors> {
+    let path = format!("data/{locale}/summoner.json");
+    let json: SummonerJson = load_json(version, &path, client).await?;
+
+    Ok(json
+        .data
+        .into_values()
+        .filter_map(|spell| Some((spell.id.parse().ok()?, spell.name)))
+        .collect())
+}
+
+async fn load_runes(
+    version: &str,
+    locale: &str,
+    client: &reqwest::Client,
+) -> Result<HashMap<i64, String>, Errors> {
+    let path = format!("data/{locale}/runesReforged.json");
+    let json: Vec<RuneTree> = load_json(version, &path, client).await?;
+
+    let mut runes = HashMap::new();
+    for tree in json {
+        for rune in tree.slots.into_iter().flat_map(|slot| slot.runes) {
+            runes.insert(rune.id, rune.name);
+        }
+        runes.insert(tree.id, tree.name);
+    }
+
+    Ok(runes)
+}
+
+/// Decodes any image format data dragon serves into something egui can display
+fn decode_image(bytes: &[u8]) -> Result<ColorImage, image::ImageError> {
+    let image = image::load_from_memory(bytes)?.into_rgba8();
+    let size = [image.width() as usize, image.height() as usize];
+
+    Ok(ColorImage::from_rgba_unmultiplied(size, image.as_raw()))
+}
+
+fn icon_path(id: i16) -> String {
+    format!("img/profileicon/{id}.png")
+}
+
 //noinspection SpellCheckingInspection
 async fn get_icon(
     id: i16,
     version: &str,
     client: &reqwest::Client,
 ) -> Result<Bytes, reqwest::Error> {
-    let res = client
-        .get(format!(
-            "https://ddragon.leagueoflegends.com/cdn/{version}/img/profileicon/{id}.png"
-        ))
-        .send()
-        .await?;
-
-    res.bytes().await
+    cache::get_asset(version, &icon_path(id), client).await
 }
 
 #[derive(Debug)]
 pub enum Errors {
     Request(reqwest::Error),
+    Json(serde_json::Error),
+    Image(image::ImageError),
 }
 
 impl From<reqwest::Error> for Errors {
@@ -421,6 +661,12 @@ impl From<reqwest::Error> for Errors {
     }
 }
 
+impl From<serde_json::Error> for Errors {
+    fn from(value: serde_json::Error) -> Self {
+        Errors::Json(value)
+    }
+}
+
 impl Display for Errors {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -428,6 +674,8 @@ impl Display for Errors {
             "{}",
             match self {
                 Errors::Request(err) => err.to_string(),
+                Errors::Json(err) => err.to_string(),
+                Errors::Image(err) => err.to_string(),
             }
         )
     }
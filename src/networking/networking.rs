@@ -25,13 +25,14 @@ pub async fn fetch_match_summaries(
     tag_line: &str,
     region_id: &str,
     role: &[u8],
+    champion: &[i16],
     page: u8,
     client: &reqwest::Client,
 ) -> Result<structs::PlayerMatchSummaries, reqwest::Error> {
     request(
         MATCH_SUMMARIES,
         FetchMatchSummaries {
-            champion_id: &[],
+            champion_id: champion,
             page,
             queue_type: &[],
             duo_riot_user_name: "",
@@ -0,0 +1,967 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Datelike, Local, TimeZone, Timelike};
+
+use crate::structs::{MatchStats, MatchSummary, Team};
+
+/// How many of the newest matches are shown in the recent form strip
+pub const FORM_LENGTH: usize = 20;
+
+/// Totals over a list of matches, everything is averaged per game unless it says otherwise
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileStats {
+    pub games: usize,
+    pub wins: usize,
+    pub kills: f64,
+    pub deaths: f64,
+    pub assists: f64,
+    pub kda: f64,
+    pub cs_per_min: f64,
+    pub kill_participation: f64,
+    pub vision_score: f64,
+    pub vision_per_min: f64,
+    /// Newest first, `true` is a win
+    pub form: Vec<bool>,
+}
+
+impl ProfileStats {
+    pub fn winrate(&self) -> f64 {
+        percentage(self.wins, self.games)
+    }
+
+    pub fn losses(&self) -> usize {
+        self.games - self.wins
+    }
+}
+
+/// Aggregates a list of matches that is ordered newest first, `None` if there are no matches
+pub fn profile_stats(summaries: &[MatchSummary]) -> Option<ProfileStats> {
+    if summaries.is_empty() {
+        return None;
+    }
+
+    let games = summaries.len();
+    let average = |value: fn(&MatchSummary) -> f64| {
+        summaries.iter().map(value).sum::<f64>() / games as f64
+    };
+
+    // The KDA is over every game, so one deathless game doesn't skew it
+    let kills: i64 = summaries.iter().map(|summary| summary.kills).sum();
+    let deaths: i64 = summaries.iter().map(|summary| summary.deaths).sum();
+    let assists: i64 = summaries.iter().map(|summary| summary.assists).sum();
+
+    Some(ProfileStats {
+        games,
+        wins: summaries.iter().filter(|summary| summary.win).count(),
+        kills: kills as f64 / games as f64,
+        deaths: deaths as f64 / games as f64,
+        assists: assists as f64 / games as f64,
+        kda: (kills + assists) as f64 / deaths.max(1) as f64,
+        cs_per_min: average(MatchSummary::cs_per_min),
+        kill_participation: average(|summary| summary.kill_participation as f64),
+        vision_score: average(|summary| summary.vision_score as f64),
+        vision_per_min: average(MatchSummary::vision_per_min),
+        form: summaries
+            .iter()
+            .take(FORM_LENGTH)
+            .map(|summary| summary.win)
+            .collect(),
+    })
+}
+
+/// Wins and scores over some group of matches
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Record {
+    pub games: usize,
+    pub wins: usize,
+    pub kills: i64,
+    pub deaths: i64,
+    pub assists: i64,
+}
+
+impl Record {
+    pub fn add(&mut self, summary: &MatchSummary) {
+        self.games += 1;
+        self.wins += summary.win as usize;
+        self.kills += summary.kills;
+        self.deaths += summary.deaths;
+        self.assists += summary.assists;
+    }
+
+    pub fn losses(&self) -> usize {
+        self.games - self.wins
+    }
+
+    pub fn winrate(&self) -> f64 {
+        percentage(self.wins, self.games)
+    }
+
+    pub fn kda(&self) -> f64 {
+        (self.kills + self.assists) as f64 / self.deaths.max(1) as f64
+    }
+
+    /// Kills, deaths and assists per game
+    pub fn average_kda(&self) -> (f64, f64, f64) {
+        let games = self.games.max(1) as f64;
+        (
+            self.kills as f64 / games,
+            self.deaths as f64 / games,
+            self.assists as f64 / games,
+        )
+    }
+}
+
+/// How a player does on one champion
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChampionStats {
+    pub champion_id: i64,
+    pub record: Record,
+    pub cs_per_min: f64,
+    /// The creation time of the newest match on the champion, in milliseconds
+    pub last_played: i64,
+}
+
+/// Every champion that was played in the matches, most played first
+pub fn champion_pool(summaries: &[MatchSummary]) -> Vec<ChampionStats> {
+    let mut pool: HashMap<i64, ChampionStats> = HashMap::new();
+
+    for summary in summaries {
+        let stats = pool.entry(summary.champion_id).or_insert(ChampionStats {
+            champion_id: summary.champion_id,
+            record: Record::default(),
+            cs_per_min: 0.0,
+            last_played: summary.match_creation_time,
+        });
+
+        stats.record.add(summary);
+        stats.last_played = stats.last_played.max(summary.match_creation_time);
+        // Summed up for now, it's averaged once every game is counted
+        stats.cs_per_min += summary.cs_per_min();
+    }
+
+    let mut pool: Vec<_> = pool.into_values().collect();
+    for stats in &mut pool {
+        stats.cs_per_min /= stats.record.games as f64;
+    }
+    pool.sort_by(|a, b| {
+        b.record
+            .games
+            .cmp(&a.record.games)
+            .then(b.last_played.cmp(&a.last_played))
+    });
+    pool
+}
+
+/// How a player does in one role, the role is u.gg's id for it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoleStats {
+    pub role: i64,
+    pub record: Record,
+}
+
+/// Every role that was played in the matches, most played first
+pub fn role_distribution(summaries: &[MatchSummary]) -> Vec<RoleStats> {
+    let mut roles: HashMap<i64, Record> = HashMap::new();
+    for summary in summaries {
+        roles.entry(summary.role).or_default().add(summary);
+    }
+
+    let mut roles: Vec<_> = roles
+        .into_iter()
+        .map(|(role, record)| RoleStats { role, record })
+        .collect();
+    roles.sort_by(|a, b| b.record.games.cmp(&a.record.games).then(a.role.cmp(&b.role)));
+    roles
+}
+
+/// Matchups with fewer games than this are too noisy to call one of the hardest
+pub const MIN_MATCHUP_GAMES: usize = 2;
+
+/// How a player does against one champion in their lane
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchupStats {
+    pub opponent_id: i64,
+    pub record: Record,
+}
+
+/// The players on the searched player's team, and the ones against them
+pub fn teams(summary: &MatchSummary) -> Option<(&[Team], &[Team])> {
+    let is_player = |player: &Team| {
+        player.riot_user_name.eq_ignore_ascii_case(&summary.riot_user_name)
+            && player.riot_tag_line.eq_ignore_ascii_case(&summary.riot_tag_line)
+    };
+
+    if summary.team_a.iter().any(is_player) {
+        Some((&summary.team_a, &summary.team_b))
+    } else if summary.team_b.iter().any(is_player) {
+        Some((&summary.team_b, &summary.team_a))
+    } else {
+        None
+    }
+}
+
+/// Whoever played the same role on the other team
+pub fn lane_opponent(summary: &MatchSummary) -> Option<&Team> {
+    let (_, opponents) = teams(summary)?;
+    opponents.iter().find(|player| player.role == summary.role)
+}
+
+/// Every champion and role pair the player has a lane opponent in, most played first
+pub fn played_lanes(summaries: &[MatchSummary]) -> Vec<(i64, i64)> {
+    let mut lanes: HashMap<(i64, i64), usize> = HashMap::new();
+    for summary in summaries.iter().filter(|summary| lane_opponent(summary).is_some()) {
+        *lanes.entry((summary.champion_id, summary.role)).or_default() += 1;
+    }
+
+    let mut lanes: Vec<_> = lanes.into_iter().collect();
+    lanes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    lanes.into_iter().map(|(lane, _)| lane).collect()
+}
+
+/// The player's record against every lane opponent, only on `lane` if there is one
+pub fn lane_matchups(summaries: &[MatchSummary], lane: Option<(i64, i64)>) -> Vec<MatchupStats> {
+    let mut matchups: HashMap<i64, Record> = HashMap::new();
+
+    for summary in summaries {
+        if lane.is_some_and(|lane| lane != (summary.champion_id, summary.role)) {
+            continue;
+        }
+        if let Some(opponent) = lane_opponent(summary) {
+            matchups.entry(opponent.champion_id).or_default().add(summary);
+        }
+    }
+
+    let mut matchups: Vec<_> = matchups
+        .into_iter()
+        .map(|(opponent_id, record)| MatchupStats {
+            opponent_id,
+            record,
+        })
+        .collect();
+    matchups.sort_by(|a, b| {
+        b.record
+            .games
+            .cmp(&a.record.games)
+            .then(a.opponent_id.cmp(&b.opponent_id))
+    });
+    matchups
+}
+
+/// The matchups with the lowest winrate, out of those that were played often enough
+pub fn hardest_matchups(matchups: &[MatchupStats], count: usize) -> Vec<&MatchupStats> {
+    let mut hardest: Vec<_> = matchups
+        .iter()
+        .filter(|matchup| matchup.record.games >= MIN_MATCHUP_GAMES)
+        .collect();
+    hardest.sort_by(|a, b| {
+        a.record
+            .winrate()
+            .total_cmp(&b.record.winrate())
+            .then(b.record.games.cmp(&a.record.games))
+    });
+    hardest.truncate(count);
+    hardest
+}
+
+/// This many games in a row with someone means they are most likely queueing together
+pub const PREMADE_STREAK: usize = 3;
+
+/// Someone who was on the player's team more than once
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeammateStats {
+    pub riot_user_name: String,
+    pub riot_tag_line: String,
+    pub record: Record,
+    /// The most games in a row they were on the player's team
+    pub longest_streak: usize,
+}
+
+impl TeammateStats {
+    pub fn is_premade(&self) -> bool {
+        self.longest_streak >= PREMADE_STREAK
+    }
+}
+
+/// Everyone the player was on a team with at least twice, most games together first
+pub fn frequent_teammates(summaries: &[MatchSummary]) -> Vec<TeammateStats> {
+    // Riot IDs aren't case sensitive, the streak is the index of their last game and its length
+    let mut teammates: HashMap<(String, String), (TeammateStats, usize, usize)> = HashMap::new();
+
+    for (index, summary) in summaries.iter().enumerate() {
+        let Some((team, _)) = teams(summary) else {
+            continue;
+        };
+
+        for player in team {
+            if player.riot_user_name.eq_ignore_ascii_case(&summary.riot_user_name)
+                && player.riot_tag_line.eq_ignore_ascii_case(&summary.riot_tag_line)
+            {
+                continue;
+            }
+
+            let key = (
+                player.riot_user_name.to_lowercase(),
+                player.riot_tag_line.to_lowercase(),
+            );
+            let (stats, last_index, streak) = teammates.entry(key).or_insert_with(|| {
+                let stats = TeammateStats {
+                    riot_user_name: player.riot_user_name.clone(),
+                    riot_tag_line: player.riot_tag_line.clone(),
+                    record: Record::default(),
+                    longest_streak: 0,
+                };
+                (stats, index, 0)
+            });
+
+            *streak = if *streak > 0 && *last_index + 1 == index {
+                *streak + 1
+            } else {
+                1
+            };
+            *last_index = index;
+
+            stats.record.add(summary);
+            stats.longest_streak = stats.longest_streak.max(*streak);
+        }
+    }
+
+    let mut teammates: Vec<_> = teammates
+        .into_values()
+        .map(|(stats, _, _)| stats)
+        .filter(|stats| stats.record.games > 1)
+        .collect();
+    teammates.sort_by(|a, b| {
+        b.record
+            .games
+            .cmp(&a.record.games)
+            .then(b.longest_streak.cmp(&a.longest_streak))
+            .then(a.riot_user_name.cmp(&b.riot_user_name))
+    });
+    teammates
+}
+
+/// A break longer than this between two matches starts a new session, in milliseconds
+pub const SESSION_GAP: i64 = 60 * 60 * 1000;
+
+/// Losing this many in a row is when it's time to stop queueing
+pub const TILT_STREAK: usize = 3;
+
+/// Matches that were played one after another without a long break
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    /// When the first match started and the last one ended, in milliseconds
+    pub start: i64,
+    pub end: i64,
+    pub record: Record,
+    /// The LP gained over the session, `None` if none of it was ranked
+    pub lp: Option<i64>,
+    pub longest_loss_streak: usize,
+}
+
+impl Session {
+    pub fn tilted(&self) -> bool {
+        self.longest_loss_streak >= TILT_STREAK
+    }
+}
+
+/// When a match ended, in milliseconds like its creation time
+fn match_end(summary: &MatchSummary) -> i64 {
+    summary.match_creation_time + summary.match_duration * 1000
+}
+
+/// Splits matches that are ordered newest first into sessions, which are also newest first
+pub fn sessions(summaries: &[MatchSummary]) -> Vec<Session> {
+    let mut sessions: Vec<Session> = vec![];
+    let mut loss_streak = 0;
+
+    // Played in order, so the streaks and gaps are easy to follow
+    for summary in summaries.iter().rev() {
+        let lp = summary.lp_info.as_ref().and_then(|info| info.lp);
+
+        match sessions.last_mut() {
+            Some(session) if summary.match_creation_time - session.end <= SESSION_GAP => {
+                session.end = session.end.max(match_end(summary));
+                if let Some(lp) = lp {
+                    *session.lp.get_or_insert(0) += lp;
+                }
+            }
+            _ => {
+                loss_streak = 0;
+                sessions.push(Session {
+                    start: summary.match_creation_time,
+                    end: match_end(summary),
+                    record: Record::default(),
+                    lp,
+                    longest_loss_streak: 0,
+                });
+            }
+        }
+
+        let session = sessions.last_mut().unwrap();
+        session.record.add(summary);
+        loss_streak = if summary.win { 0 } else { loss_streak + 1 };
+        session.longest_loss_streak = session.longest_loss_streak.max(loss_streak);
+    }
+
+    sessions.reverse();
+    sessions
+}
+
+/// How many of the newest matches were lost in a row
+pub fn current_loss_streak(summaries: &[MatchSummary]) -> usize {
+    summaries.iter().take_while(|summary| !summary.win).count()
+}
+
+/// Records by the day of the week, starting on Monday, and the hour a match started
+pub type Heatmap = [[Record; 24]; 7];
+
+/// When the player plays and how well, in the local timezone
+pub fn time_heatmap(summaries: &[MatchSummary]) -> Heatmap {
+    let mut heatmap: Heatmap = Default::default();
+
+    for summary in summaries {
+        let Some(time) = Local.timestamp_millis_opt(summary.match_creation_time).single() else {
+            continue;
+        };
+        let day = time.weekday().num_days_from_monday() as usize;
+        heatmap[day][time.hour() as usize].add(summary);
+    }
+
+    heatmap
+}
+
+/// The major and minor version of a patch, u.gg separates them with either `.` or `_`
+pub fn patch(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split(['.', '_']);
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+/// How a player did on one patch, and how their champion pool changed from the one before
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchStats {
+    pub patch: (u32, u32),
+    pub record: Record,
+    /// Every champion that was played and how often, most played first
+    pub champions: Vec<(i64, usize)>,
+    /// Champions that weren't played on the last patch before this one with any matches,
+    /// both are empty for the oldest patch since there is nothing to compare it to
+    pub picked_up: Vec<i64>,
+    pub dropped: Vec<i64>,
+}
+
+/// Every patch that was played on, newest first
+pub fn patch_breakdown(summaries: &[MatchSummary]) -> Vec<PatchStats> {
+    let mut patches: BTreeMap<(u32, u32), (Record, HashMap<i64, usize>)> = BTreeMap::new();
+    for summary in summaries {
+        let Some(patch) = patch(&summary.version) else {
+            continue;
+        };
+        let (record, champions) = patches.entry(patch).or_default();
+        record.add(summary);
+        *champions.entry(summary.champion_id).or_default() += 1;
+    }
+
+    let mut breakdown: Vec<PatchStats> = vec![];
+    let mut previous: Option<HashMap<i64, usize>> = None;
+
+    // Oldest first, so each patch can be compared to the one before it
+    for (patch, (record, champions)) in patches {
+        let (mut picked_up, mut dropped) = match &previous {
+            Some(previous) => (
+                champions
+                    .keys()
+                    .filter(|id| !previous.contains_key(id))
+                    .copied()
+                    .collect(),
+                previous
+                    .keys()
+                    .filter(|id| !champions.contains_key(id))
+                    .copied()
+                    .collect(),
+            ),
+            None => (vec![], vec![]),
+        };
+        picked_up.sort_unstable();
+        dropped.sort_unstable();
+
+        let mut played: Vec<_> = champions.iter().map(|(id, games)| (*id, *games)).collect();
+        played.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        breakdown.push(PatchStats {
+            patch,
+            record,
+            champions: played,
+            picked_up,
+            dropped,
+        });
+        previous = Some(champions);
+    }
+
+    breakdown.reverse();
+    breakdown
+}
+
+/// `part` out of `total` as a percentage, zero if there is nothing to divide
+pub fn percentage(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    100.0 * part as f64 / total as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::LpInfo;
+
+    const MINUTE: i64 = 60 * 1000;
+
+    fn player(name: &str, tag_line: &str) -> Team {
+        Team {
+            champion_id: 1,
+            hard_carry: 0.0,
+            role: 4,
+            riot_user_name: name.to_owned(),
+            riot_tag_line: tag_line.to_owned(),
+            teamplay: 0.0,
+        }
+    }
+
+    /// A 30 minute match for `Player#NA1` starting `start` minutes in, with no one else on either team
+    fn summary(start: i64, win: bool) -> MatchSummary {
+        MatchSummary {
+            assists: 0,
+            champion_id: 1,
+            cs: 0,
+            damage: 0,
+            deaths: 0,
+            gold: 0,
+            items: vec![],
+            jungle_cs: 0,
+            kill_participation: 0,
+            kills: 0,
+            level: 18,
+            lp_info: None,
+            match_creation_time: start * MINUTE,
+            match_duration: 30 * 60,
+            match_id: start,
+            maximum_kill_streak: 0,
+            primary_style: 0,
+            ps_hard_carry: 0,
+            ps_team_play: 0,
+            queue_type: "ranked_solo_5x5".to_owned(),
+            region_id: "na1".to_owned(),
+            role: 4,
+            runes: vec![],
+            sub_style: 0,
+            riot_user_name: "Player".to_owned(),
+            riot_tag_line: "NA1".to_owned(),
+            summoner_spells: vec![],
+            team_a: vec![player("Player", "NA1")],
+            team_b: vec![],
+            version: "14.1.1".to_owned(),
+            vision_score: 0,
+            win,
+        }
+    }
+
+    fn ranked(start: i64, win: bool, lp: Option<i64>) -> MatchSummary {
+        MatchSummary {
+            lp_info: Some(LpInfo { lp, placement: None }),
+            ..summary(start, win)
+        }
+    }
+
+    /// The matches are written oldest first, but everything expects them newest first
+    fn newest_first(mut summaries: Vec<MatchSummary>) -> Vec<MatchSummary> {
+        summaries.reverse();
+        summaries
+    }
+
+    #[test]
+    fn profile_stats_need_a_match() {
+        assert_eq!(profile_stats(&[]), None);
+    }
+
+    #[test]
+    fn profile_stats_divide_a_deathless_kda_by_one() {
+        let scored = |kills, assists| MatchSummary {
+            kills,
+            assists,
+            ..summary(0, true)
+        };
+
+        let stats = profile_stats(&[scored(3, 2), scored(5, 0)]).unwrap();
+
+        assert_eq!(stats.kills, 4.0);
+        assert_eq!(stats.deaths, 0.0);
+        assert_eq!(stats.assists, 1.0);
+        assert_eq!(stats.kda, 10.0);
+    }
+
+    #[test]
+    fn profile_stats_skip_the_rates_of_matches_without_a_duration() {
+        let farmed = |cs, match_duration| MatchSummary {
+            cs,
+            match_duration,
+            ..summary(0, false)
+        };
+
+        let stats = profile_stats(&[farmed(100, 0), farmed(100, 10 * 60)]).unwrap();
+
+        assert_eq!(stats.cs_per_min, 5.0);
+        assert_eq!(stats.winrate(), 0.0);
+        assert_eq!(stats.losses(), 2);
+    }
+
+    #[test]
+    fn profile_stats_form_only_has_the_newest_matches() {
+        let summaries: Vec<_> = (0..FORM_LENGTH as i64 + 5)
+            .map(|start| summary(start, start == 0))
+            .collect();
+
+        let stats = profile_stats(&summaries).unwrap();
+
+        assert_eq!(stats.games, FORM_LENGTH + 5);
+        assert_eq!(stats.form.len(), FORM_LENGTH);
+        assert!(stats.form[0]);
+        assert!(!stats.form[1..].iter().any(|win| *win));
+    }
+
+    #[test]
+    fn champion_pool_is_empty_without_matches() {
+        assert!(champion_pool(&[]).is_empty());
+    }
+
+    #[test]
+    fn champion_pool_breaks_ties_by_the_last_played() {
+        let on = |champion_id, start, cs| MatchSummary {
+            champion_id,
+            cs,
+            ..summary(start, true)
+        };
+        // The matches don't have to be in order to find when a champion was last played
+        let summaries = [on(2, 90, 0), on(1, 120, 300), on(2, 0, 0), on(1, 30, 0), on(3, 60, 0)];
+
+        let pool = champion_pool(&summaries);
+
+        let order: Vec<_> = pool.iter().map(|stats| stats.champion_id).collect();
+        assert_eq!(order, vec![1, 2, 3]);
+        assert_eq!(pool[0].last_played, 120 * MINUTE);
+        assert_eq!(pool[1].last_played, 90 * MINUTE);
+        assert_eq!(pool[0].cs_per_min, 5.0);
+        assert_eq!(pool[1].cs_per_min, 0.0);
+    }
+
+    #[test]
+    fn role_distribution_breaks_ties_by_the_role() {
+        let in_role = |role, win| MatchSummary {
+            role,
+            ..summary(0, win)
+        };
+        let summaries = [
+            in_role(5, true),
+            in_role(3, false),
+            in_role(5, false),
+            in_role(1, true),
+            in_role(1, true),
+        ];
+
+        let roles = role_distribution(&summaries);
+
+        let order: Vec<_> = roles.iter().map(|stats| stats.role).collect();
+        assert_eq!(order, vec![1, 5, 3]);
+        assert_eq!(roles[0].record.winrate(), 100.0);
+        assert_eq!(roles[1].record.winrate(), 50.0);
+        assert_eq!(roles[2].record.losses(), 1);
+        assert!(role_distribution(&[]).is_empty());
+    }
+
+    /// A match as `champion_id` in `role` against `opponent_id` in the same role
+    fn against(champion_id: i64, role: i64, opponent_id: i64, win: bool) -> MatchSummary {
+        MatchSummary {
+            champion_id,
+            role,
+            team_a: vec![Team {
+                champion_id,
+                role,
+                ..player("Player", "NA1")
+            }],
+            team_b: vec![Team {
+                champion_id: opponent_id,
+                role,
+                ..player("Opponent", "NA1")
+            }],
+            ..summary(0, win)
+        }
+    }
+
+    #[test]
+    fn lane_opponent_is_on_the_other_team_in_the_same_role() {
+        // The player is found on either team, whatever case their Riot ID is in
+        let mut swapped = against(1, 4, 2, true);
+        std::mem::swap(&mut swapped.team_a, &mut swapped.team_b);
+        swapped.riot_user_name = "PLAYER".to_owned();
+        assert_eq!(lane_opponent(&swapped).unwrap().riot_user_name, "Opponent");
+
+        let mut roaming = against(1, 4, 2, true);
+        roaming.team_b[0].role = 5;
+        assert!(lane_opponent(&roaming).is_none());
+
+        let mut renamed = against(1, 4, 2, true);
+        renamed.riot_tag_line = "EUW".to_owned();
+        assert!(lane_opponent(&renamed).is_none());
+    }
+
+    #[test]
+    fn lane_matchups_only_count_the_lane() {
+        let summaries = [
+            against(1, 4, 2, true),
+            against(1, 4, 2, false),
+            against(1, 4, 3, true),
+            against(1, 3, 2, true),
+            against(5, 4, 2, true),
+        ];
+
+        let matchups = lane_matchups(&summaries, Some((1, 4)));
+
+        assert_eq!(matchups.len(), 2);
+        assert_eq!(matchups[0].opponent_id, 2);
+        assert_eq!(matchups[0].record.games, 2);
+        assert_eq!(matchups[1].opponent_id, 3);
+        assert_eq!(lane_matchups(&summaries, None)[0].record.games, 4);
+        assert_eq!(played_lanes(&summaries), vec![(1, 4), (1, 3), (5, 4)]);
+    }
+
+    #[test]
+    fn hardest_matchups_need_enough_games() {
+        let summaries = [
+            // Never won, but once isn't enough to tell
+            against(1, 4, 2, false),
+            against(1, 4, 3, true),
+            against(1, 4, 3, false),
+            against(1, 4, 4, false),
+            against(1, 4, 4, false),
+            against(1, 4, 4, true),
+            against(1, 4, 5, true),
+            against(1, 4, 5, true),
+        ];
+        let matchups = lane_matchups(&summaries, None);
+
+        let hardest: Vec<_> = hardest_matchups(&matchups, 2)
+            .iter()
+            .map(|matchup| matchup.opponent_id)
+            .collect();
+
+        assert_eq!(hardest, vec![4, 3]);
+        assert_eq!(hardest_matchups(&matchups, 5).len(), 3);
+        assert!(hardest_matchups(&matchups, 0).is_empty());
+    }
+
+    /// A match with `teammates` on the player's team and someone else on the other one
+    fn with_teammates(start: i64, win: bool, teammates: &[(&str, &str)]) -> MatchSummary {
+        let mut summary = summary(start, win);
+        summary
+            .team_a
+            .extend(teammates.iter().map(|(name, tag_line)| player(name, tag_line)));
+        summary.team_b.push(player("Opponent", "NA1"));
+        summary
+    }
+
+    #[test]
+    fn frequent_teammates_merge_riot_ids_and_skip_opponents() {
+        let summaries = [
+            with_teammates(0, true, &[("Friend", "NA1"), ("Duo", "EUW")]),
+            with_teammates(30, false, &[("Friend", "NA1"), ("Once", "NA1")]),
+            with_teammates(60, true, &[("friend", "na1"), ("duo", "euw")]),
+        ];
+
+        let teammates = frequent_teammates(&summaries);
+
+        // Someone who was only there once, and the opponent in every match, aren't counted
+        let names: Vec<_> = teammates.iter().map(|stats| stats.riot_user_name.as_str()).collect();
+        assert_eq!(names, vec!["Friend", "Duo"]);
+        assert_eq!(teammates[0].record.games, 3);
+        assert_eq!(teammates[0].record.wins, 2);
+        assert_eq!(teammates[1].record.games, 2);
+    }
+
+    #[test]
+    fn frequent_teammates_streaks_break_on_a_match_without_them() {
+        let summaries = [
+            with_teammates(0, true, &[("Friend", "NA1"), ("Duo", "NA1")]),
+            with_teammates(30, true, &[("Friend", "NA1"), ("Duo", "NA1")]),
+            with_teammates(60, true, &[("Duo", "NA1")]),
+            with_teammates(90, true, &[("Friend", "NA1"), ("Duo", "NA1")]),
+            // The player isn't in this one, so it breaks everyone's streak
+            MatchSummary {
+                riot_user_name: "Someone".to_owned(),
+                ..with_teammates(120, true, &[("Duo", "NA1")])
+            },
+            with_teammates(150, true, &[("Friend", "NA1"), ("Duo", "NA1")]),
+        ];
+
+        let teammates = frequent_teammates(&summaries);
+
+        assert_eq!(teammates[0].riot_user_name, "Duo");
+        assert_eq!(teammates[0].record.games, 5);
+        assert_eq!(teammates[0].longest_streak, PREMADE_STREAK + 1);
+        assert!(teammates[0].is_premade());
+        assert_eq!(teammates[1].riot_user_name, "Friend");
+        assert_eq!(teammates[1].longest_streak, PREMADE_STREAK - 1);
+        assert!(!teammates[1].is_premade());
+        assert!(frequent_teammates(&[]).is_empty());
+    }
+
+    #[test]
+    fn time_heatmap_uses_the_local_day_and_hour() {
+        let at = |day, hour, minute, win| {
+            let time = Local.with_ymd_and_hms(2024, 1, day, hour, minute, 0).unwrap();
+            MatchSummary {
+                match_creation_time: time.timestamp_millis(),
+                ..summary(0, win)
+            }
+        };
+        // The 1st of January 2024 is a Monday, and the 7th the Sunday after it
+        let summaries = [
+            at(1, 0, 0, true),
+            at(1, 0, 59, false),
+            at(1, 1, 0, true),
+            at(7, 23, 59, true),
+        ];
+
+        let heatmap = time_heatmap(&summaries);
+
+        assert_eq!(heatmap[0][0].games, 2);
+        assert_eq!(heatmap[0][0].wins, 1);
+        assert_eq!(heatmap[0][1].games, 1);
+        assert_eq!(heatmap[6][23].games, 1);
+        let games: usize = heatmap.iter().flatten().map(|record| record.games).sum();
+        assert_eq!(games, summaries.len());
+    }
+
+    #[test]
+    fn sessions_split_after_the_gap() {
+        let gap = SESSION_GAP / MINUTE;
+        let summaries = newest_first(vec![
+            summary(0, true),
+            // Exactly the gap after the last match ended is still the same session
+            summary(30 + gap, true),
+            summary(60 + 2 * gap + 1, false),
+        ]);
+
+        let sessions = sessions(&summaries);
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].record.games, 1);
+        assert_eq!(sessions[0].start, (60 + 2 * gap + 1) * MINUTE);
+        assert_eq!(sessions[1].record.games, 2);
+        assert_eq!(sessions[1].start, 0);
+        assert_eq!(sessions[1].end, (60 + gap) * MINUTE);
+    }
+
+    #[test]
+    fn sessions_reset_the_loss_streak() {
+        let gap = SESSION_GAP / MINUTE;
+        let summaries = newest_first(vec![
+            summary(0, false),
+            summary(30, false),
+            summary(60, false),
+            // The streak from the session before doesn't carry over
+            summary(90 + gap + 1, false),
+            summary(120 + gap + 1, true),
+            summary(150 + gap + 1, false),
+        ]);
+
+        let sessions = sessions(&summaries);
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].longest_loss_streak, 1);
+        assert!(!sessions[0].tilted());
+        assert_eq!(sessions[1].longest_loss_streak, 3);
+        assert!(sessions[1].tilted());
+    }
+
+    #[test]
+    fn sessions_only_sum_known_lp() {
+        let gap = SESSION_GAP / MINUTE;
+        let summaries = newest_first(vec![
+            summary(0, true),
+            ranked(30, true, Some(20)),
+            ranked(60, false, None),
+            ranked(90, false, Some(-5)),
+            summary(120 + gap + 1, true),
+            ranked(150 + gap + 1, true, None),
+        ]);
+
+        let sessions = sessions(&summaries);
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].lp, None);
+        assert_eq!(sessions[1].lp, Some(15));
+    }
+
+    #[test]
+    fn patch_parses_game_and_data_dragon_versions() {
+        assert_eq!(patch("14.5.565.5505"), Some((14, 5)));
+        assert_eq!(patch("14_5"), Some((14, 5)));
+        assert_eq!(patch("14.5.1"), Some((14, 5)));
+        assert_eq!(patch("lolpatch_7.17"), None);
+        assert_eq!(patch("14"), None);
+    }
+
+    /// A match as `champion_id` on the game version `version`
+    fn on_patch(champion_id: i64, version: &str) -> MatchSummary {
+        MatchSummary {
+            champion_id,
+            version: version.to_owned(),
+            ..summary(0, true)
+        }
+    }
+
+    #[test]
+    fn patch_breakdown_compares_to_the_last_played_patch() {
+        // Nothing was played on 14.2, and versions that can't be parsed are left out
+        let summaries = [
+            on_patch(3, "14_3_1"),
+            on_patch(1, "14.3.1"),
+            on_patch(1, "14.3.1"),
+            on_patch(4, "unknown"),
+            on_patch(2, "14.1.1"),
+            on_patch(1, "14.1.1"),
+        ];
+
+        let breakdown = patch_breakdown(&summaries);
+
+        assert_eq!(breakdown.len(), 2);
+        assert_eq!(breakdown[0].patch, (14, 3));
+        assert_eq!(breakdown[0].record.games, 3);
+        assert_eq!(breakdown[0].champions, vec![(1, 2), (3, 1)]);
+        assert_eq!(breakdown[0].picked_up, vec![3]);
+        assert_eq!(breakdown[0].dropped, vec![2]);
+        // The oldest patch has nothing to compare to
+        assert_eq!(breakdown[1].patch, (14, 1));
+        assert!(breakdown[1].picked_up.is_empty());
+        assert!(breakdown[1].dropped.is_empty());
+    }
+
+    #[test]
+    fn patch_breakdown_orders_patches_by_number() {
+        let summaries = [
+            on_patch(1, "14.10.1"),
+            on_patch(1, "14.9.1"),
+            on_patch(1, "13.24.1"),
+        ];
+
+        let patches: Vec<_> = patch_breakdown(&summaries)
+            .iter()
+            .map(|stats| stats.patch)
+            .collect();
+
+        assert_eq!(patches, vec![(14, 10), (14, 9), (13, 24)]);
+        assert!(patch_breakdown(&[]).is_empty());
+        assert!(patch_breakdown(&[on_patch(1, "")]).is_empty());
+    }
+}
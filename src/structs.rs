@@ -36,6 +36,8 @@ pub struct MatchSummary {
     pub kill_participation: i64,
     pub kills: i64,
     pub level: i64,
+    // Only ranked matches have this
+    pub lp_info: Option<LpInfo>,
     pub match_creation_time: i64,
     pub match_duration: i64,
     pub match_id: i64,
@@ -58,6 +60,30 @@ pub struct MatchSummary {
     pub win: bool,
 }
 
+impl MatchStats for MatchSummary {
+    fn totals(&self) -> MatchTotals {
+        MatchTotals {
+            kills: self.kills,
+            deaths: self.deaths,
+            assists: self.assists,
+            cs: self.cs,
+            jungle_cs: self.jungle_cs,
+            gold: self.gold,
+            damage: self.damage,
+            vision_score: self.vision_score,
+            match_duration: self.match_duration,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LpInfo {
+    // How much LP the match gained or lost
+    pub lp: Option<i64>,
+    pub placement: Option<i64>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Team {
@@ -216,6 +242,44 @@ pub enum Tag {
     Tank,
 }
 
+/// Deserialize Summoner Spells
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SummonerJson {
+    pub version: String,
+    pub data: HashMap<String, SummonerSpell>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SummonerSpell {
+    // This is the same swap as ChampData, the numeric id is under "key"
+    #[serde(rename = "id")]
+    pub key: String,
+    #[serde(rename = "key")]
+    pub id: String,
+    pub name: String,
+}
+
+/// Deserialize Runes, the trees are in this too
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RuneTree {
+    pub id: i64,
+    pub key: String,
+    pub name: String,
+    pub slots: Vec<RuneSlot>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RuneSlot {
+    pub runes: Vec<Rune>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Rune {
+    pub id: i64,
+    pub key: String,
+    pub name: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GetMatch {
     pub data: MatchData,
@@ -233,6 +297,64 @@ pub struct Match {
     pub match_summary: FullMatchSummary,
     pub performance_score: Vec<PerformanceScore>,
     pub winning_team: i64,
+    // Where every player's own stats are, the teams in the summary only have who played what
+    #[serde(default)]
+    pub historical_data: Option<HistoricalData>,
+}
+
+impl Match {
+    /// Gives every player in the summary their stats from the post game data
+    pub fn attach_post_game_data(&mut self) {
+        let post_game_data = self
+            .historical_data
+            .as_ref()
+            .and_then(|data| data.post_game_data.as_deref())
+            .unwrap_or_default();
+        let summary = &mut self.match_summary;
+        let match_duration = summary.match_duration;
+        for player in summary.team_a.iter_mut().chain(summary.team_b.iter_mut()) {
+            player.match_duration = match_duration;
+            player.post_game = post_game_data
+                .iter()
+                .find(|data| {
+                    data.riot_user_name
+                        .as_deref()
+                        .is_some_and(|name| name.eq_ignore_ascii_case(&player.riot_user_name))
+                        && data
+                            .riot_tag_line
+                            .as_deref()
+                            .is_some_and(|tag_line| tag_line.eq_ignore_ascii_case(&player.riot_tag_line))
+                })
+                .cloned();
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoricalData {
+    #[serde(default)]
+    pub post_game_data: Option<Vec<PostGameData>>,
+}
+
+/// One player's stats after a match, any of them can be missing, which only leaves that stat blank
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PostGameData {
+    pub riot_user_name: Option<String>,
+    pub riot_tag_line: Option<String>,
+    pub kills: Option<i64>,
+    pub deaths: Option<i64>,
+    pub assists: Option<i64>,
+    pub cs: Option<i64>,
+    pub jungle_cs: Option<i64>,
+    pub gold: Option<i64>,
+    pub level: Option<i64>,
+    pub items: Option<Vec<i64>>,
+    pub runes: Option<Vec<i64>>,
+    pub primary_style: Option<i64>,
+    pub sub_style: Option<i64>,
+    pub summoner_spells: Option<Vec<i64>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -271,6 +393,22 @@ pub struct FullMatchSummary {
     pub win: bool,
 }
 
+impl MatchStats for FullMatchSummary {
+    fn totals(&self) -> MatchTotals {
+        MatchTotals {
+            kills: self.kills,
+            deaths: self.deaths,
+            assists: self.assists,
+            cs: self.cs,
+            jungle_cs: self.jungle_cs,
+            gold: self.gold,
+            damage: self.damage,
+            vision_score: self.vision_score,
+            match_duration: self.match_duration,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PerformanceScore {
@@ -289,6 +427,32 @@ pub struct MatchTeam {
     pub riot_user_name: String,
     pub riot_tag_line: String,
     pub team_id: i64,
+    // Both of these are filled in by Match::attach_post_game_data
+    #[serde(skip)]
+    pub post_game: Option<PostGameData>,
+    #[serde(skip)]
+    pub match_duration: i64,
+}
+
+impl MatchStats for MatchTeam {
+    /// Stats missing from the post game data count as 0
+    fn totals(&self) -> MatchTotals {
+        let stat = |stat: fn(&PostGameData) -> Option<i64>| {
+            self.post_game.as_ref().and_then(stat).unwrap_or_default()
+        };
+        MatchTotals {
+            kills: stat(|data| data.kills),
+            deaths: stat(|data| data.deaths),
+            assists: stat(|data| data.assists),
+            cs: stat(|data| data.cs),
+            jungle_cs: stat(|data| data.jungle_cs),
+            gold: stat(|data| data.gold),
+            damage: self.damage,
+            // Only the searched player's vision is sent
+            vision_score: 0,
+            match_duration: self.match_duration,
+        }
+    }
 }
 
 /// Deserialize Player Suggestions
@@ -314,3 +478,66 @@ pub struct PlayerInfoSuggestion {
     pub riot_user_name: String,
     pub riot_tag_line: String,
 }
+
+/// The searched player's totals for a match, which both kinds of match summary have
+#[derive(Debug, Clone, Copy)]
+pub struct MatchTotals {
+    pub kills: i64,
+    pub deaths: i64,
+    pub assists: i64,
+    pub cs: i64,
+    pub jungle_cs: i64,
+    pub gold: i64,
+    pub damage: i64,
+    pub vision_score: i64,
+    // In seconds
+    pub match_duration: i64,
+}
+
+/// Stats worked out from a match's totals, the same way for every kind of match summary
+pub trait MatchStats {
+    fn totals(&self) -> MatchTotals;
+
+    /// Lane minions and jungle camps combined
+    fn total_cs(&self) -> i64 {
+        let totals = self.totals();
+        totals.cs + totals.jungle_cs
+    }
+
+    /// Kills and assists per death, deathless games are divided by one instead
+    fn kda(&self) -> f64 {
+        let totals = self.totals();
+        kda_ratio(totals.kills, totals.deaths, totals.assists)
+    }
+
+    fn cs_per_min(&self) -> f64 {
+        per_minute(self.total_cs(), self.totals().match_duration)
+    }
+
+    fn gold_per_min(&self) -> f64 {
+        let totals = self.totals();
+        per_minute(totals.gold, totals.match_duration)
+    }
+
+    fn damage_per_min(&self) -> f64 {
+        let totals = self.totals();
+        per_minute(totals.damage, totals.match_duration)
+    }
+
+    fn vision_per_min(&self) -> f64 {
+        let totals = self.totals();
+        per_minute(totals.vision_score, totals.match_duration)
+    }
+}
+
+/// `value` averaged over a match's duration, which is in seconds
+fn per_minute(value: i64, match_duration: i64) -> f64 {
+    if match_duration <= 0 {
+        return 0.0;
+    }
+    value as f64 / (match_duration as f64 / 60.0)
+}
+
+fn kda_ratio(kills: i64, deaths: i64, assists: i64) -> f64 {
+    (kills + assists) as f64 / deaths.max(1) as f64
+}
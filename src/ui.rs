@@ -1,53 +1,92 @@
 use crate::structs::{
-    self, ChampData, GetMatch, Match, MatchSummary, OverallRanking, PlayerProfileSuggestions,
-    PlayerSuggestions, RankScore,
+    self, ChampData, GetMatch, Match, MatchStats, MatchSummary, PostGameData, OverallRanking, PlayerProfileSuggestions,
+    PlayerSuggestions, RankScore, Team,
+};
+use crate::filter::{self, MatchListOptions, MatchSort, Outcome, Query};
+use crate::stats::{
+    self, ChampionStats, Heatmap, PatchStats, ProfileStats, Record, RoleStats, TeammateStats,
 };
 use crate::{spawn_gui_shit, Errors, SharedState, SHARED_STATE};
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use eframe::egui::{
-    self, Button, ComboBox, Image, Label, RichText, TextBuffer, TextEdit, Ui, Vec2,
+    self, Button, ComboBox, Image, Label, PointerButton, RichText, TextBuffer, TextEdit, Ui, Vec2,
 };
 use eframe::epaint::Color32;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::sync::atomic::AtomicBool;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, LazyLock, RwLock};
 use tokio::runtime::Runtime;
 
 #[derive(Debug)]
 pub enum Results {
-    MatchSum(Result<structs::PlayerMatchSummaries, Errors>),
-    PlayerUpdate(Result<structs::UpdatePlayer, Errors>),
-    Ranking(Result<structs::PlayerRanking, Errors>),
-    PlayerInfo(Result<structs::PlayerInfo, Errors>),
-    MatchDetails(Result<(Box<GetMatch>, i64), Errors>),
+    MatchSum(ProfileRequest, Result<structs::PlayerMatchSummaries, Errors>),
+    AllMatchSum(ProfileRequest, Result<Box<[MatchSummary]>, Errors>),
+    PlayerUpdate(ProfileRequest, Result<structs::UpdatePlayer, Errors>),
+    Ranking(ProfileRequest, Result<structs::PlayerRanking, Errors>),
+    // This is also used to refresh favorites, which don't belong to a tab
+    // The region is sent back since the profile info doesn't include it
+    PlayerInfo(Option<ProfileRequest>, &'static str, Result<structs::PlayerInfo, Errors>),
+    MatchDetails(TabId, Result<(Box<GetMatch>, i64), Errors>),
     PlayerSuggestions(Result<PlayerSuggestions, Errors>),
     PlayerIcon(Errors),
     Versions(Errors),
     ChampJson(Errors),
     ChampImage(Errors),
+    ItemImage(Errors),
+    ChampsRefreshed(Result<(), Errors>),
+    StaticData(Errors),
+}
+
+impl Results {
+    /// The tab a result should be applied to, if it's for a specific profile
+    pub fn tab(&self) -> Option<TabId> {
+        match self {
+            Results::MatchSum(request, _)
+            | Results::AllMatchSum(request, _)
+            | Results::PlayerUpdate(request, _)
+            | Results::Ranking(request, _) => Some(request.tab),
+            Results::MatchDetails(tab, _) => Some(*tab),
+            Results::PlayerInfo(request, _, _) => request.map(|request| request.tab),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum Payload {
+    /// Every page of matches at once, used for the stats panel
+    AllMatchSummaries {
+        tab: ProfileRequest,
+        name: Arc<String>,
+        tag_line: Arc<String>,
+        roles: Option<u8>,
+        champion: Option<i16>,
+        region_id: &'static str,
+    },
     MatchSummaries {
+        tab: ProfileRequest,
         name: Arc<String>,
         tag_line: Arc<String>,
         roles: Option<u8>,
+        champion: Option<i16>,
         page: u8,
         region_id: &'static str,
     },
     UpdatePlayer {
+        tab: ProfileRequest,
         name: Arc<String>,
         tag_line: Arc<String>,
         region_id: &'static str,
     },
     PlayerRanking {
+        tab: ProfileRequest,
         name: Arc<String>,
         tag_line: Arc<String>,
         region_id: &'static str,
     },
     PlayerInfo {
+        tab: Option<ProfileRequest>,
         name: Arc<String>,
         tag_line: Arc<String>,
         version_index: usize,
@@ -55,13 +94,31 @@ pub enum Payload {
     },
     GetVersions,
     GetChampInfo {
-        url: String,
+        version: String,
+        locale: &'static str,
+    },
+    RefreshChampInfo {
+        locale: &'static str,
+    },
+    GetStaticData {
+        version: String,
+        locale: &'static str,
     },
     GetChampImage {
-        url: String,
+        version: String,
+        key: String,
+        id: i64,
+    },
+    GetChampSprite {
+        version: String,
+        sheet: String,
+    },
+    GetItemImage {
+        version: String,
         id: i64,
     },
     GetMatchDetails {
+        tab: TabId,
         name: Arc<String>,
         tag_line: Arc<String>,
         version: String,
@@ -85,22 +142,175 @@ pub struct MyEguiApp {
     // Actively tracked state of GUI components
     pub refresh_enabled: bool,
     pub update_enabled: bool,
+
+    // The profile that is being shown, every other open profile is in tabs
+    // The slot for the active tab in tabs is left empty until it's switched away from
+    pub tab: Tab,
+    pub tabs: Vec<Tab>,
+    pub active_tab: usize,
+    pub next_tab_id: TabId,
+
+    pub data_dragon: DataDragon,
+    pub player_suggestions: PlayerSuggestions,
+    // The last asset that failed to load, anything that was waiting on it has a placeholder instead
+    pub asset_error: Option<String>,
+
+    // Players that are kept between sessions, in the order the user put them in
+    pub favorites: Vec<Favorite>,
+    pub recent_searches: Vec<RecentSearch>,
+
+    // Runtime so the threads don't close
+    _rt: Runtime,
+}
+
+pub type TabId = u64;
+
+/// The tab a profile request was sent from, and what it was showing at the time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProfileRequest {
+    pub tab: TabId,
+    pub navigation: u64,
+}
+
+/// Everything about a single open profile, so several can be loaded side by side
+pub struct Tab {
+    pub id: TabId,
+    // Bumped every time the profile is loaded again, results for anything before that are dropped
+    pub navigation: u64,
     pub finished_match_summaries: bool,
     pub page: u8,
     pub role: u8,
+    pub champion: Option<i64>,
 
     // Values used for data lookup
     pub active_player: String,
     pub riot_user_name: Arc<String>,
     pub riot_tag_line: Arc<String>,
-    pub data_dragon: DataDragon,
+    pub region: &'static str,
 
     // These three are loaded lazily, and may or may not exist!
     pub player_data: PlayerData,
-    pub player_suggestions: PlayerSuggestions,
 
-    // Runtime so the threads don't close
-    _rt: Runtime,
+    // Profiles that were navigated away from, and where the match list was scrolled to
+    pub history: History,
+    pub scroll_offset: f32,
+    pub pending_scroll: Option<f32>,
+
+    // Whether the stats panel covers the loaded page or every page
+    pub stats_all_pages: bool,
+
+    // What is shown below the profile header, and how the breakdowns are sorted
+    pub view: ProfileView,
+    pub champion_sort: ChampionColumn,
+    pub champion_sort_descending: bool,
+    // The champion and role the matchups are shown for, every lane if it's None
+    pub matchup_lane: Option<(i64, i64)>,
+    // How the loaded matches are sorted and filtered without fetching them again
+    pub match_list: MatchListOptions,
+    // The text the query in match_list was parsed from, and why it couldn't be if it wasn't
+    pub match_query: String,
+    pub match_query_error: Option<filter::ParseError>,
+}
+
+impl Tab {
+    pub fn new(id: TabId, region: &'static str) -> Self {
+        Self {
+            id,
+            navigation: 0,
+            finished_match_summaries: true,
+            page: 1,
+            role: 5,
+            champion: None,
+            active_player: Default::default(),
+            riot_user_name: Default::default(),
+            riot_tag_line: Default::default(),
+            region,
+            player_data: PlayerData {
+                match_data_map: Default::default(),
+                match_summaries: None,
+                rank_scores: None,
+                ranking: None,
+                icon_id: -1,
+                all_match_summaries: None,
+                all_pages_started: false,
+                all_pages_error: None,
+                all_pages_navigation: 0,
+            },
+            history: History::default(),
+            scroll_offset: 0.0,
+            pending_scroll: None,
+            stats_all_pages: false,
+            view: ProfileView::Matches,
+            champion_sort: ChampionColumn::Games,
+            champion_sort_descending: true,
+            matchup_lane: None,
+            match_list: MatchListOptions::default(),
+            match_query: String::new(),
+            match_query_error: None,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        if self.riot_user_name.is_empty() {
+            "New Tab".to_owned()
+        } else {
+            format!("{}#{}", self.riot_user_name, self.riot_tag_line)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProfileView {
+    Matches,
+    Champions,
+    Roles,
+    Matchups,
+    Teammates,
+    Sessions,
+    Schedule,
+    Patches,
+}
+
+impl ProfileView {
+    const ALL: [(ProfileView, &'static str); 8] = [
+        (ProfileView::Matches, "Matches"),
+        (ProfileView::Champions, "Champions"),
+        (ProfileView::Roles, "Roles"),
+        (ProfileView::Matchups, "Matchups"),
+        (ProfileView::Teammates, "Teammates"),
+        (ProfileView::Sessions, "Sessions"),
+        (ProfileView::Schedule, "Schedule"),
+        (ProfileView::Patches, "Patches"),
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChampionColumn {
+    Champion,
+    Games,
+    Winrate,
+    Kda,
+    CsPerMin,
+    LastPlayed,
+}
+
+impl PlayerData {
+    /// The matches the stats and breakdowns are over, `None` while they are loading
+    pub fn stats_summaries(&self, all_pages: bool) -> Option<&[MatchSummary]> {
+        if all_pages {
+            self.all_match_summaries.as_deref()
+        } else {
+            self.match_summaries.as_deref()
+        }
+    }
+
+    /// Every page has to be loaded again, e.g. after the profile or its filters change
+    pub fn reset_all_pages(&mut self) {
+        self.all_match_summaries = None;
+        self.all_pages_started = false;
+        self.all_pages_error = None;
+        self.all_pages_navigation += 1;
+    }
 }
 
 pub struct PlayerData {
@@ -109,20 +319,136 @@ pub struct PlayerData {
     pub rank_scores: Option<Box<[RankScore]>>,
     pub ranking: Option<OverallRanking>,
     pub icon_id: i16,
+    // Only loaded once the stats are switched to cover every page
+    pub all_match_summaries: Option<Box<[MatchSummary]>>,
+    pub all_pages_started: bool,
+    // Every page isn't requested again after failing until it's retried
+    pub all_pages_error: Option<String>,
+    // Kept apart from the tab's navigation, since paging doesn't change what every page is
+    pub all_pages_navigation: u64,
+}
+
+/// A player that is kept in the favorites panel between sessions
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Favorite {
+    pub riot_user_name: String,
+    pub riot_tag_line: String,
+    pub region: String,
+    // These are from the last time the player was loaded, so they can be shown right away
+    pub icon_id: i16,
+    pub rank: Option<String>,
+}
+
+enum FavoriteAction {
+    Open(usize),
+    MoveUp(usize),
+    MoveDown(usize),
+    Remove(usize),
+}
+
+const FAVORITES_KEY: &str = "favorites";
+
+/// Everything besides favorites that is restored on startup, the window itself is saved by eframe
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct Settings {
+    region: String,
+    role: u8,
+    locale: String,
+    use_sprites: bool,
+    recent_searches: Vec<RecentSearch>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            region: "na1".to_owned(),
+            role: 5,
+            locale: LOCALES[0].0.to_owned(),
+            use_sprites: false,
+            recent_searches: vec![],
+        }
+    }
+}
+
+const SETTINGS_KEY: &str = "settings";
+
+/// A profile that was looked up from the search bar, newest first
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RecentSearch {
+    pub riot_user_name: String,
+    pub riot_tag_line: String,
+    pub region: String,
+}
+
+const MAX_RECENT_SEARCHES: usize = 10;
+
+/// The data dragon languages that can be shown with the default fonts
+const LOCALES: [(&str, &str); 8] = [
+    ("en_US", "English (US)"),
+    ("en_GB", "English (UK)"),
+    ("de_DE", "Deutsch"),
+    ("es_ES", "Español"),
+    ("fr_FR", "Français"),
+    ("it_IT", "Italiano"),
+    ("pl_PL", "Polski"),
+    ("pt_BR", "Português"),
+];
+
+enum TabAction {
+    Switch(usize),
+    Close(usize),
+    New,
+}
+
+/// Profiles that can be navigated back and forward to, like in a browser
+#[derive(Default)]
+pub struct History {
+    pub back: Vec<ProfileSnapshot>,
+    pub forward: Vec<ProfileSnapshot>,
+}
+
+/// Everything needed to show a profile again without querying it
+pub struct ProfileSnapshot {
+    pub riot_user_name: Arc<String>,
+    pub riot_tag_line: Arc<String>,
+    pub region: &'static str,
+    pub role: u8,
+    pub champion: Option<i64>,
+    pub page: u8,
+    pub finished_match_summaries: bool,
+    pub scroll_offset: f32,
+    pub match_summaries: Option<Box<[MatchSummary]>>,
+    pub rank_scores: Option<Box<[RankScore]>>,
+    pub ranking: Option<OverallRanking>,
+    pub icon_id: i16,
 }
 
 /// This stores all data dragon assets that are being used at any given time, that are not in the shared state
 pub struct DataDragon {
     pub ver_started: bool,
     pub champ_info_started: bool,
+    pub static_data_started: bool,
+    pub favorites_started: bool,
+    // Portraits are sliced out of the sprite sheets instead of being fetched one by one
+    pub use_sprites: bool,
+    pub sprites_started: bool,
+    // Only done once, a champ that is still missing afterward just isn't on data dragon yet
+    pub champ_refresh_started: bool,
+    pub missing_champs: HashSet<i64>,
     pub region_id_name: HashMap<&'static str, &'static str>,
-    pub region: &'static str,
+    // The language assets are loaded in, changing it only takes effect on the next launch
+    pub locale: &'static str,
+    pub selected_locale: &'static str,
 }
 
 /// Struct representing all the data of a champ we display
 pub struct Champ {
     pub key: String,
     pub name: String,
+    // The data dragon version this champ was loaded from, which isn't always the latest
+    pub version: String,
+    pub sprite: Sprite,
     // This is updated from the thread pool, and as such, can be locked
     pub image: RwLock<Option<egui::TextureHandle>>,
     // The champ struct is passed around a lot, but this allows me to only use
@@ -135,22 +461,59 @@ impl Debug for Champ {
         f.debug_struct("Champ")
             .field("key", &self.key)
             .field("name", &self.name)
+            .field("version", &self.version)
+            .field("sprite", &self.sprite)
             .field("image_started", &self.image_started)
             .finish()
     }
 }
 
+/// Where a champ's portrait is located in the data dragon sprite sheets
+#[derive(Debug)]
+pub struct Sprite {
+    pub sheet: String,
+    pub rect: egui::Rect,
+}
+
 impl From<ChampData> for Champ {
     fn from(val: ChampData) -> Champ {
+        let image = val.image;
         Champ {
             key: val.key,
             name: val.name,
+            version: val.version,
+            sprite: Sprite {
+                sheet: image.sprite,
+                rect: egui::Rect::from_min_size(
+                    egui::pos2(image.x as f32, image.y as f32),
+                    Vec2::new(image.w as f32, image.h as f32),
+                ),
+            },
             image: RwLock::new(None),
             image_started: AtomicBool::new(false),
         }
     }
 }
 
+/// Stands in for champs that u.gg knows about, but champion.json doesn't have yet
+static UNKNOWN_CHAMP: LazyLock<Champ> = LazyLock::new(|| Champ {
+    key: String::new(),
+    name: "Unknown".to_owned(),
+    version: String::new(),
+    sprite: Sprite {
+        sheet: String::new(),
+        rect: egui::Rect::NOTHING,
+    },
+    image: RwLock::new(None),
+    // The placeholder portrait is set on startup, so nothing should ever be fetched for it
+    image_started: AtomicBool::new(true),
+});
+
+/// Looks up a champ, falling back to a placeholder if it's missing from champion.json
+pub fn champ(champs: &HashMap<i64, Champ>, id: i64) -> &Champ {
+    champs.get(&id).unwrap_or(&UNKNOWN_CHAMP)
+}
+
 static ROLES: [&str; 6] = ["Top", "Jungle", "Mid", "ADC", "Support", "None"];
 
 const UGG_ROLES_REVERSED: [&str; 8] =
@@ -160,45 +523,75 @@ impl MyEguiApp {
     pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
         let (_rt, sender, receiver) = spawn_gui_shit(&_cc.egui_ctx);
 
+        *UNKNOWN_CHAMP.image.write().unwrap() = Some(SHARED_STATE.placeholder(&_cc.egui_ctx));
+
+        let settings: Settings = _cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, SETTINGS_KEY))
+            .unwrap_or_default();
+
+        let region_id_name = HashMap::from([("na1", "NA"), ("euw1", "EUW")]);
+        // Anything saved by an older version that doesn't exist anymore falls back to the default
+        let region = region_id_name
+            .get_key_value(settings.region.as_str())
+            .map_or("na1", |(region, _)| *region);
+        let locale = LOCALES
+            .iter()
+            .find(|(locale, _)| *locale == settings.locale)
+            .map_or(LOCALES[0].0, |(locale, _)| *locale);
+        let role = if (settings.role as usize) < ROLES.len() {
+            settings.role
+        } else {
+            5
+        };
+
+        let mut tab = Tab::new(0, region);
+        tab.role = role;
+
         Self {
-            active_player: Default::default(),
-            riot_user_name: Default::default(),
-            riot_tag_line: Default::default(),
             shared_state: &SHARED_STATE,
-            role: 5,
             refresh_enabled: false,
             update_enabled: false,
+            tab,
+            tabs: vec![Tab::new(0, region)],
+            active_tab: 0,
+            next_tab_id: 1,
             data_dragon: DataDragon {
                 ver_started: false,
                 champ_info_started: false,
-                region_id_name: HashMap::from([("na1", "NA"), ("euw1", "EUW")]),
-                region: "na1",
-            },
-            player_data: PlayerData {
-                match_data_map: Default::default(),
-                match_summaries: None,
-                rank_scores: None,
-                ranking: None,
-                icon_id: -1,
+                static_data_started: false,
+                favorites_started: false,
+                use_sprites: settings.use_sprites,
+                sprites_started: false,
+                champ_refresh_started: false,
+                missing_champs: HashSet::new(),
+                region_id_name,
+                locale,
+                selected_locale: locale,
             },
             messenger: sender,
             receiver,
-            page: 1,
-            finished_match_summaries: true,
+            asset_error: None,
             player_suggestions: PlayerSuggestions {
                 data: PlayerProfileSuggestions {
                     player_profile_suggestions: vec![],
                 },
             },
+            favorites: _cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, FAVORITES_KEY))
+                .unwrap_or_default(),
+            recent_searches: settings.recent_searches,
             _rt,
         }
     }
 
     fn update_player(&self) {
         self.send_message(Payload::UpdatePlayer {
-            name: self.riot_user_name.clone(),
-            tag_line: self.riot_tag_line.clone(),
-            region_id: self.data_dragon.region,
+            tab: self.profile_request(),
+            name: self.tab.riot_user_name.clone(),
+            tag_line: self.tab.riot_tag_line.clone(),
+            region_id: self.tab.region,
         });
     }
 
@@ -213,20 +606,146 @@ impl MyEguiApp {
         }
     }
 
+    fn load_sprites(&mut self, champs: &HashMap<i64, Champ>) {
+        if self.data_dragon.sprites_started {
+            return;
+        }
+
+        // Champs from a refresh can be on a newer version than the rest
+        let mut sheets: Vec<(&str, &str)> = champs
+            .values()
+            .map(|champ| (champ.version.as_str(), champ.sprite.sheet.as_str()))
+            .collect();
+        sheets.sort_unstable();
+        sheets.dedup();
+
+        for (version, sheet) in sheets {
+            self.send_message(Payload::GetChampSprite {
+                version: version.to_owned(),
+                sheet: sheet.to_owned(),
+            });
+        }
+
+        self.data_dragon.sprites_started = true;
+    }
+
+    /// Loads another player's profile in `region`, switching the tab to it
+    ///
+    /// The profile that was loaded before is kept in the history, so it can be navigated back to
+    fn open_profile(&mut self, region: &'static str, name: String, tag_line: String) {
+        self.push_history();
+        self.zero_player();
+        self.tab.region = region;
+        self.tab.active_player = format!("{}#{}", name, tag_line);
+        self.tab.riot_user_name = Arc::new(name);
+        self.tab.riot_tag_line = Arc::new(tag_line);
+        self.update_matches();
+    }
+
+    fn is_favorite(&self) -> bool {
+        self.favorites.iter().any(|favorite| {
+            favorite.region == self.tab.region
+                && favorite
+                    .riot_user_name
+                    .eq_ignore_ascii_case(&self.tab.riot_user_name)
+                && favorite
+                    .riot_tag_line
+                    .eq_ignore_ascii_case(&self.tab.riot_tag_line)
+        })
+    }
+
+    fn add_favorite(&mut self) {
+        self.favorites.push(Favorite {
+            riot_user_name: self.tab.riot_user_name.to_string(),
+            riot_tag_line: self.tab.riot_tag_line.to_string(),
+            region: self.tab.region.to_owned(),
+            icon_id: self.tab.player_data.icon_id,
+            rank: self.tab.player_data.rank_scores.as_deref().and_then(rank_summary),
+        });
+    }
+
+    fn favorite_action(&mut self, action: FavoriteAction) {
+        match action {
+            FavoriteAction::Open(index) => {
+                let favorite = self.favorites[index].clone();
+                let region = self
+                    .data_dragon
+                    .region_id_name
+                    .get_key_value(favorite.region.as_str())
+                    .map_or(self.tab.region, |(region, _)| region);
+                self.open_profile(region, favorite.riot_user_name, favorite.riot_tag_line);
+            }
+            FavoriteAction::MoveUp(index) => {
+                if index > 0 {
+                    self.favorites.swap(index, index - 1);
+                }
+            }
+            FavoriteAction::MoveDown(index) => {
+                if index + 1 < self.favorites.len() {
+                    self.favorites.swap(index, index + 1);
+                }
+            }
+            FavoriteAction::Remove(index) => {
+                self.favorites.remove(index);
+            }
+        }
+    }
+
+    /// Moves the searched profile to the top of the recent searches
+    fn add_recent_search(&mut self) {
+        let search = RecentSearch {
+            riot_user_name: self.tab.riot_user_name.to_string(),
+            riot_tag_line: self.tab.riot_tag_line.to_string(),
+            region: self.tab.region.to_owned(),
+        };
+
+        self.recent_searches.retain(|recent| {
+            !(recent.region == search.region
+                && recent.riot_user_name.eq_ignore_ascii_case(&search.riot_user_name)
+                && recent.riot_tag_line.eq_ignore_ascii_case(&search.riot_tag_line))
+        });
+        self.recent_searches.insert(0, search);
+        self.recent_searches.truncate(MAX_RECENT_SEARCHES);
+    }
+
+    fn open_recent_search(&mut self, search: RecentSearch) {
+        let region = self
+            .data_dragon
+            .region_id_name
+            .get_key_value(search.region.as_str())
+            .map_or(self.tab.region, |(region, _)| region);
+        self.open_profile(region, search.riot_user_name, search.riot_tag_line);
+    }
+
+    /// Clears everything loaded for the profile, so none of it is shown while the next one loads
     fn zero_player(&mut self) {
-        self.player_data.icon_id = -1;
-        self.player_data.match_summaries = None;
-        self.player_data.rank_scores = None;
-        self.player_data.ranking = None;
-        self.page = 1;
-        self.finished_match_summaries = true;
+        self.tab.player_data.icon_id = -1;
+        self.tab.player_data.match_summaries = None;
+        self.tab.player_data.rank_scores = None;
+        self.tab.player_data.ranking = None;
+        self.tab.player_data.reset_all_pages();
+        self.tab.page = 1;
+        self.tab.finished_match_summaries = true;
     }
 }
 
 impl eframe::App for MyEguiApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // The side buttons on a mouse work like they would in a browser
+        let (back, forward) = ctx.input(|input| {
+            (
+                input.pointer.button_pressed(PointerButton::Extra1),
+                input.pointer.button_pressed(PointerButton::Extra2),
+            )
+        });
+        if back {
+            self.go_back();
+        } else if forward {
+            self.go_forward();
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
-            if !self.active_player.is_empty() {
+            if !self.tab.active_player.is_empty() {
                 self.refresh_enabled = true;
                 self.update_enabled = true;
             } else {
@@ -234,7 +753,7 @@ impl eframe::App for MyEguiApp {
                 self.update_enabled = false;
             };
 
-            let Some(versions) = self.shared_state.versions.get() else {
+            let Some(versions) = self.shared_state.versions() else {
                 self.load_version(ctx);
                 egui::CentralPanel::default().show_inside(ui, |ui| {
                     ui.spinner();
@@ -243,25 +762,80 @@ impl eframe::App for MyEguiApp {
                 return;
             };
 
-            let Some(champs) = self.shared_state.champs.get() else {
+            let Some(champs) = self.shared_state.champs() else {
                 if !self.data_dragon.champ_info_started {
                     self.send_message(Payload::GetChampInfo {
-                        url: format!(
-                            "https://ddragon.leagueoflegends.com/cdn/{}/data/en_US/champion.json",
-                            versions[0]
-                        ),
+                        version: versions[0].clone(),
+                        locale: self.data_dragon.locale,
                     });
                     self.data_dragon.champ_info_started = true;
                 }
 
+                if let Ok(Results::ChampJson(err)) = self.receiver.try_recv() {
+                    self.asset_error = Some(err.to_string());
+                }
+
                 egui::CentralPanel::default().show_inside(ui, |ui| {
-                    ui.spinner();
+                    let Some(err) = &self.asset_error else {
+                        ui.spinner();
+                        return;
+                    };
+
+                    ui.label(RichText::new(format!("✖ {}", err)).color(Color32::RED));
+                    if ui.button("Retry").clicked() {
+                        self.data_dragon.champ_info_started = false;
+                        self.asset_error = None;
+                    }
                 });
 
                 return;
             };
 
-            self.update_data(versions, champs);
+            if !self.data_dragon.static_data_started {
+                self.send_message(Payload::GetStaticData {
+                    version: versions[0].clone(),
+                    locale: self.data_dragon.locale,
+                });
+                self.data_dragon.static_data_started = true;
+            }
+
+            if self.data_dragon.use_sprites {
+                self.load_sprites(&champs);
+            }
+
+            self.load_favorites();
+            self.load_all_pages();
+
+            self.update_data(&versions, &champs);
+
+            let mut tab_action = None;
+
+            egui::TopBottomPanel::top("Tabs").show_inside(ui, |ui| {
+                tab_action = tab_bar(ui, &self.tab, &self.tabs, self.active_tab);
+
+                if let Some(err) = &self.asset_error {
+                    let dismissed = ui
+                        .horizontal(|ui| {
+                            ui.label(
+                                RichText::new(format!("✖ Failed to load an image: {}", err))
+                                    .small()
+                                    .color(Color32::RED),
+                            );
+                            ui.small_button("Dismiss").clicked()
+                        })
+                        .inner;
+                    if dismissed {
+                        self.asset_error = None;
+                    }
+                }
+            });
+
+            match tab_action {
+                Some(TabAction::Switch(index)) => self.switch_tab(index),
+                Some(TabAction::Close(index)) => self.close_tab(index),
+                Some(TabAction::New) => self.new_tab(),
+                None => {}
+            }
 
             egui::SidePanel::left("Left Panel")
                 // 15% of available width
@@ -270,12 +844,28 @@ impl eframe::App for MyEguiApp {
                 .show_inside(ui, |ui| {
                     let full_height = ui.available_height();
 
+                    ui.horizontal(|ui| {
+                        let half = Vec2::new(ui.available_width() * 0.45, 0.0);
+
+                        let button = Button::new("⏴").min_size(half);
+                        if ui.add_enabled(!self.tab.history.back.is_empty(), button).clicked() {
+                            self.go_back();
+                        }
+
+                        let button = Button::new("⏵").min_size(half);
+                        if ui.add_enabled(!self.tab.history.forward.is_empty(), button).clicked() {
+                            self.go_forward();
+                        }
+                    });
+
+                    ui.add_space(0.01 * full_height);
+
                     ui.with_layout(
                         egui::Layout::left_to_right(eframe::emath::Align::Min),
                         |ui| {
                             ui.label("Player: ");
 
-                            let search_bar = TextEdit::singleline(&mut self.active_player);
+                            let search_bar = TextEdit::singleline(&mut self.tab.active_player);
 
                             let mut search_bar =
                                 ui.add_sized(Vec2::new(ui.available_width(), 0.0), search_bar);
@@ -283,6 +873,7 @@ impl eframe::App for MyEguiApp {
                             search_bar.sense.focusable = true;
                             let id = "player_suggestions".into();
 
+                            let mut searched = None;
                             egui::popup_below_widget(ui, id, &search_bar, |ui| {
                                 for x in &self.player_suggestions.data.player_profile_suggestions {
                                     let mut label = ui.selectable_label(
@@ -291,19 +882,14 @@ impl eframe::App for MyEguiApp {
                                     );
                                     label.sense.click = true;
                                     if label.clicked() {
-                                        self.active_player = x.riot_user_name.clone();
-                                        self.riot_user_name = x.riot_user_name.clone().into();
-                                        self.riot_tag_line = x.riot_tag_line.clone().into();
-                                        self.update_matches(
-                                            &self.riot_user_name,
-                                            &self.riot_tag_line,
-                                        );
+                                        searched =
+                                            Some((x.riot_user_name.clone(), x.riot_tag_line.clone()));
                                     }
                                 }
                             });
 
                             if search_bar.has_focus()
-                                && !self.active_player.is_empty()
+                                && !self.tab.active_player.is_empty()
                                 && !self
                                     .player_suggestions
                                     .data
@@ -318,26 +904,55 @@ impl eframe::App for MyEguiApp {
                             }
 
                             if search_bar.clicked()
-                                && !self.active_player.ends_with(' ')
-                                && !self.active_player.is_empty()
-                                && self.active_player.contains('#')
+                                && !self.tab.active_player.ends_with(' ')
+                                && !self.tab.active_player.is_empty()
+                                && self.tab.active_player.contains('#')
                             {
-                                let (name, tag) = self.active_player.split_once('#').unwrap();
-                                self.riot_user_name = Arc::new(name.to_owned());
-                                self.riot_tag_line = Arc::new(tag.to_owned());
-                                self.update_matches(&self.riot_user_name, &self.riot_tag_line);
+                                let (name, tag) = self.tab.active_player.split_once('#').unwrap();
+                                searched = Some((name.to_owned(), tag.to_owned()));
+                            }
+
+                            if let Some((name, tag_line)) = searched {
+                                self.open_profile(self.tab.region, name, tag_line);
+                                self.add_recent_search();
                             }
 
-                            // We need to update suggestions
+                            // We need to update suggestions, the loaded profile stays until a search is made
                             if search_bar.changed() {
-                                self.zero_player();
                                 self.send_message(Payload::GetPlayerSuggestions {
-                                    name: Arc::new(self.active_player.clone()),
+                                    name: Arc::new(self.tab.active_player.clone()),
                                 })
                             }
                         },
                     );
 
+                    let mut recent_search = None;
+                    egui::CollapsingHeader::new("Recent Searches")
+                        .id_source("Recent Searches")
+                        .show(ui, |ui| {
+                            if self.recent_searches.is_empty() {
+                                ui.label("No Recent Searches");
+                            }
+
+                            for search in &self.recent_searches {
+                                let region = self
+                                    .data_dragon
+                                    .region_id_name
+                                    .get(search.region.as_str())
+                                    .copied()
+                                    .unwrap_or(search.region.as_str());
+                                let riot_id =
+                                    format!("{}#{} ({})", search.riot_user_name, search.riot_tag_line, region);
+                                if ui.link(riot_id).clicked() {
+                                    recent_search = Some(search.clone());
+                                }
+                            }
+                        });
+
+                    if let Some(search) = recent_search {
+                        self.open_recent_search(search);
+                    }
+
                     ui.add_space(0.01 * full_height);
 
                     ui.with_layout(
@@ -348,46 +963,76 @@ impl eframe::App for MyEguiApp {
                             ui.set_width(third * 3.1);
 
                             let button = Button::new("⬅").min_size(Vec2::new(third, 0.0));
-                            if ui.add_enabled(self.page > 1, button).clicked() {
-                                self.page -= 1;
-                                self.update_matches(&self.riot_user_name, &self.riot_tag_line)
+                            if ui.add_enabled(self.tab.page > 1, button).clicked() {
+                                self.tab.page -= 1;
+                                self.update_matches()
                             }
 
-                            let label = Label::new(format!("{}", self.page));
+                            let label = Label::new(format!("{}", self.tab.page));
                             ui.add_sized(Vec2::new(ui.available_width() - third, 0.0), label);
 
                             let button = Button::new("➡").min_size(Vec2::new(third, 0.0));
                             if ui
-                                .add_enabled(!self.finished_match_summaries, button)
+                                .add_enabled(!self.tab.finished_match_summaries, button)
                                 .clicked()
                             {
-                                self.page += 1;
-                                self.update_matches(&self.riot_user_name, &self.riot_tag_line)
+                                self.tab.page += 1;
+                                self.update_matches()
                             }
                         },
                     );
 
                     ui.add_space(0.01 * full_height);
 
+                    let mut role = self.tab.role;
                     ui.horizontal(|ui| {
                         ui.label("Role: ");
                         ComboBox::from_id_source("Role Select")
-                            .selected_text(ROLES[self.role as usize])
+                            .selected_text(ROLES[self.tab.role as usize])
                             .width(ui.available_width())
                             .show_ui(ui, |ui| {
                                 ROLES.iter().enumerate().for_each(|(index, value)| {
-                                    ui.selectable_value(&mut self.role, index as u8, *value);
+                                    ui.selectable_value(&mut role, index as u8, *value);
                                 });
                             });
                     });
 
+                    if role != self.tab.role {
+                        self.set_role_filter(role);
+                    }
+
+                    ui.add_space(0.01 * full_height);
+
+                    ui.horizontal(|ui| {
+                        ui.label("Champion: ");
+
+                        let mut champion = self.tab.champion;
+                        let selected = champion.map_or("All", |id| champ(&champs, id).name.as_str());
+                        ComboBox::from_id_source("Champion Select")
+                            .selected_text(selected)
+                            .width(ui.available_width())
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut champion, None, "All");
+
+                                let mut sorted: Vec<_> = champs.iter().collect();
+                                sorted.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+                                for (id, champ) in sorted {
+                                    ui.selectable_value(&mut champion, Some(*id), &champ.name);
+                                }
+                            });
+
+                        if champion != self.tab.champion {
+                            self.set_champion_filter(champion);
+                        }
+                    });
+
                     ui.add_space(0.01 * full_height);
 
                     ui.horizontal(|ui| {
                         ui.label("Region: ");
 
                         ComboBox::from_id_source("regions")
-                            .selected_text(self.data_dragon.region_id_name[self.data_dragon.region])
+                            .selected_text(self.data_dragon.region_id_name[self.tab.region])
                             .width(ui.available_width())
                             .show_ui(ui, |ui| {
                                 self.data_dragon
@@ -395,7 +1040,7 @@ impl eframe::App for MyEguiApp {
                                     .iter()
                                     .for_each(|(index, name)| {
                                         ui.selectable_value(
-                                            &mut self.data_dragon.region,
+                                            &mut self.tab.region,
                                             *index,
                                             *name,
                                         );
@@ -405,10 +1050,41 @@ impl eframe::App for MyEguiApp {
 
                     ui.add_space(0.01 * full_height);
 
+                    ui.checkbox(&mut self.data_dragon.use_sprites, "Sprite Portraits");
+
+                    ui.add_space(0.01 * full_height);
+
+                    ui.horizontal(|ui| {
+                        ui.label("Language: ");
+
+                        let selected = LOCALES
+                            .iter()
+                            .find(|(locale, _)| *locale == self.data_dragon.selected_locale)
+                            .map_or(self.data_dragon.selected_locale, |(_, name)| *name);
+                        ComboBox::from_id_source("Locale Select")
+                            .selected_text(selected)
+                            .width(ui.available_width())
+                            .show_ui(ui, |ui| {
+                                for (locale, name) in LOCALES {
+                                    ui.selectable_value(
+                                        &mut self.data_dragon.selected_locale,
+                                        locale,
+                                        name,
+                                    );
+                                }
+                            });
+                    });
+
+                    if self.data_dragon.selected_locale != self.data_dragon.locale {
+                        ui.label(RichText::new("Restart to change the language").small());
+                    }
+
+                    ui.add_space(0.01 * full_height);
+
                     let button = Button::new("Refresh Player")
                         .min_size(Vec2::new(ui.available_width(), 0.0));
                     if ui.add_enabled(self.refresh_enabled, button).clicked() {
-                        self.update_matches(&self.riot_user_name, &self.riot_tag_line);
+                        self.update_matches();
                     }
 
                     ui.add_space(0.01 * full_height);
@@ -418,18 +1094,46 @@ impl eframe::App for MyEguiApp {
                     if ui.add_enabled(self.refresh_enabled, button).clicked() {
                         self.update_player();
                     }
+
+                    ui.add_space(0.01 * full_height);
+
+                    let button =
+                        Button::new("Add Favorite").min_size(Vec2::new(ui.available_width(), 0.0));
+                    let enabled = self.tab.player_data.rank_scores.is_some() && !self.is_favorite();
+                    if ui.add_enabled(enabled, button).clicked() {
+                        self.add_favorite();
+                    }
+                });
+
+            let mut favorite_action = None;
+
+            egui::SidePanel::right("Favorites")
+                .exact_width(0.15 * ui.available_width())
+                .resizable(false)
+                .show_inside(ui, |ui| {
+                    favorite_action = favorites_panel(
+                        ui,
+                        self.shared_state,
+                        &self.favorites,
+                        &self.data_dragon.region_id_name,
+                    );
                 });
 
+            if let Some(action) = favorite_action {
+                self.favorite_action(action);
+            }
+
             let height = ui.available_height();
 
-            if let Some(ranks) = &self.player_data.rank_scores {
+            let mut retry_all_pages = false;
+            if let Some(ranks) = &self.tab.player_data.rank_scores {
                 ui.add_space(0.01 * height);
 
                 egui::TopBottomPanel::top("Top Panel").show_inside(ui, |ui| {
                     ui.horizontal(|ui| {
-                        if self.player_data.icon_id != -1 {
+                        if self.tab.player_data.icon_id != -1 {
                             if let Ok(map) = self.shared_state.player_icons.try_read() {
-                                if let Some(texture) = map.get(&self.player_data.icon_id) {
+                                if let Some(texture) = map.get(&self.tab.player_data.icon_id) {
                                     ui.add(
                                         Image::new(texture).max_size(Vec2::splat(0.08 * height)),
                                     );
@@ -464,7 +1168,7 @@ impl eframe::App for MyEguiApp {
                                 ui.vertical(|ui| {
                                     ui.label(format!("Wins: {}", rank.wins));
                                     ui.label(format!("Losses: {}", rank.losses));
-                                    if let Some(ranking) = &self.player_data.ranking {
+                                    if let Some(ranking) = &self.tab.player_data.ranking {
                                         ui.label(format!(
                                             "Ranking: {} / {}",
                                             ranking.overall_ranking, ranking.total_player_count
@@ -477,119 +1181,1438 @@ impl eframe::App for MyEguiApp {
                                 ui.separator();
                             }
                         }
+
+                        if let Some(summaries) = &self.tab.player_data.match_summaries {
+                            if !summaries.is_empty() {
+                                if ranks.is_empty() {
+                                    ui.separator();
+                                }
+
+                                let count = summaries.len() as f64;
+                                let average = |score: fn(&MatchSummary) -> i64| {
+                                    summaries.iter().map(score).sum::<i64>() as f64 / count
+                                };
+
+                                ui.vertical(|ui| {
+                                    ui.label("Average Performance");
+                                    ui.label(format!(
+                                        "Hard Carry: {:.0}",
+                                        average(|summary| summary.ps_hard_carry)
+                                    ));
+                                    ui.label(format!(
+                                        "Teamplay: {:.0}",
+                                        average(|summary| summary.ps_team_play)
+                                    ));
+                                });
+
+                                ui.separator();
+
+                                let all_pages = self.tab.stats_all_pages;
+                                let summaries = self.tab.player_data.stats_summaries(all_pages);
+                                let stats = summaries.and_then(stats::profile_stats);
+                                let error = self.tab.player_data.all_pages_error.as_deref();
+                                retry_all_pages = profile_stats_panel(
+                                    ui,
+                                    stats.as_ref(),
+                                    summaries.is_none(),
+                                    all_pages.then_some(error).flatten(),
+                                    &mut self.tab.stats_all_pages,
+                                );
+                            }
+                        }
                     });
 
                     ui.add_space(0.01 * height);
                 });
             }
 
+            if retry_all_pages {
+                self.tab.player_data.all_pages_error = None;
+            }
+
             ui.add_space(0.01 * height);
 
-            egui::ScrollArea::vertical()
-                .max_height(ui.available_height())
-                .show(ui, |ui| {
-                    if let Some(summaries) = &self.player_data.match_summaries {
-                        if summaries.is_empty() {
-                            ui.label("No Recent Matches");
-                        } else {
-                            for summary in summaries.iter() {
-                                let champ = &champs[&summary.champion_id];
-                                ui.add_space(0.01 * height);
-                                let id = ui.make_persistent_id(summary.match_id);
-
-                                egui::collapsing_header::CollapsingState::load_with_default_open(
-                                    ctx, id, false,
-                                )
-                                .show_header(ui, |ui| {
-                                    if let Ok(image) = &champ.image.try_read() {
-                                        if let Some(texture) = &**image {
-                                            ui.add(
-                                                Image::new(texture)
-                                                    .max_size(Vec2::splat(0.08 * height)),
-                                            );
-                                        } else {
-                                            ui.spinner();
-                                        }
-                                    } else {
-                                        ui.spinner();
-                                    }
+            // Set when a player in one of the matches is clicked, their profile is opened afterward
+            let mut clicked_player = None;
 
-                                    ui.vertical(|ui| {
-                                        ui.horizontal(|ui| {
-                                            ui.label(&champ.name);
-                                            ui.label(UGG_ROLES_REVERSED[summary.role as usize]);
-                                            let kda = format!(
-                                                "{}/{}/{}",
-                                                summary.kills, summary.deaths, summary.assists
-                                            );
+            // Set when a row in the champion pool is clicked, it's used as the champion filter
+            let mut clicked_champion = None;
 
-                                            ui.label(kda);
-                                        });
+            ui.horizontal(|ui| {
+                for (view, name) in ProfileView::ALL {
+                    ui.selectable_value(&mut self.tab.view, view, name);
+                }
+            });
+
+            if self.tab.view == ProfileView::Matches {
+                if let Some(summaries) = &self.tab.player_data.match_summaries {
+                    match_list_controls(
+                        ui,
+                        &champs,
+                        summaries,
+                        &mut self.tab.match_list,
+                        (&mut self.tab.match_query, &mut self.tab.match_query_error),
+                    );
+                }
+            }
 
-                                        ui.horizontal(|ui| {
-                                            if summary.win {
-                                                ui.label(RichText::new("Win").color(Color32::BLUE))
-                                            } else {
-                                                ui.label(RichText::new("Loss").color(Color32::RED))
-                                            };
-                                        })
+            // Every view keeps its own scroll position
+            let mut scroll_area = egui::ScrollArea::vertical()
+                .id_source(self.tab.view)
+                .max_height(ui.available_height());
+            if let Some(offset) = self.tab.pending_scroll.take() {
+                scroll_area = scroll_area.vertical_scroll_offset(offset);
+            }
+
+            let scroll = scroll_area.show(ui, |ui| {
+                if self.tab.view != ProfileView::Matches {
+                    let all_pages = self.tab.stats_all_pages;
+                    let Some(summaries) = self.tab.player_data.stats_summaries(all_pages) else {
+                        if !self.tab.riot_user_name.is_empty() {
+                            ui.spinner();
+                        }
+                        return;
+                    };
+
+                    match self.tab.view {
+                        ProfileView::Champions => {
+                            let pool = stats::champion_pool(summaries);
+                            clicked_champion = champion_pool_table(
+                                ui,
+                                &champs,
+                                pool,
+                                (&mut self.tab.champion_sort, &mut self.tab.champion_sort_descending),
+                                0.03 * height,
+                            );
+                        }
+                        ProfileView::Roles => {
+                            role_chart(ui, &stats::role_distribution(summaries));
+                        }
+                        ProfileView::Matchups => {
+                            matchup_view(
+                                ui,
+                                &champs,
+                                summaries,
+                                &mut self.tab.matchup_lane,
+                                0.03 * height,
+                            );
+                        }
+                        ProfileView::Teammates => {
+                            let teammates = stats::frequent_teammates(summaries);
+                            clicked_player = teammate_table(ui, &teammates);
+                        }
+                        ProfileView::Sessions => session_list(ui, summaries),
+                        ProfileView::Schedule => {
+                            heatmap(ui, &stats::time_heatmap(summaries), 0.03 * height);
+                        }
+                        ProfileView::Patches => {
+                            patch_list(ui, &champs, &stats::patch_breakdown(summaries), 0.03 * height);
+                        }
+                        ProfileView::Matches => unreachable!(),
+                    }
+                    return;
+                }
+
+                if let Some(summaries) = &self.tab.player_data.match_summaries {
+                    let shown = self.tab.match_list.apply(summaries);
+                    // Days only make sense as headings while the matches are in order
+                    let by_date = self.tab.match_list.sort == MatchSort::Date;
+
+                    if summaries.is_empty() {
+                        ui.label("No Recent Matches");
+                    } else if shown.is_empty() {
+                        ui.label("No Matches Fit The Filters");
+                    } else {
+                        let now = Local::now();
+                        let mut last_day = None;
+
+                        for summary in shown {
+                            let champ = champ(&champs, summary.champion_id);
+                            let played_at = match_time(summary.match_creation_time);
+
+                            // Matches are newest first, so a new day starts whenever the date changes
+                            let day = played_at.map(|time| time.date_naive());
+                            if by_date && day != last_day {
+                                if let Some(day) = day {
+                                    ui.add_space(0.01 * height);
+                                    ui.label(RichText::new(day_heading(day, now)).strong());
+                                }
+                                last_day = day;
+                            }
+
+                            ui.add_space(0.01 * height);
+                            let id = ui.make_persistent_id(summary.match_id);
+
+                            egui::collapsing_header::CollapsingState::load_with_default_open(
+                                ctx, id, false,
+                            )
+                            .show_header(ui, |ui| {
+                                champ_image(ui, champ, 0.08 * height);
+
+                                ui.vertical(|ui| {
+                                    ui.horizontal(|ui| {
+                                        ui.label(&champ.name);
+                                        ui.label(UGG_ROLES_REVERSED[summary.role as usize]);
+                                        let kda = format!(
+                                            "{}/{}/{} ({:.2} KDA)",
+                                            summary.kills,
+                                            summary.deaths,
+                                            summary.assists,
+                                            summary.kda()
+                                        );
+
+                                        ui.label(kda);
+
+                                        if let Some(badge) = badge(summary) {
+                                            ui.label(
+                                                RichText::new(badge)
+                                                    .strong()
+                                                    .color(Color32::GOLD),
+                                            );
+                                        }
                                     });
-                                })
-                                .body(|ui| {
-                                    let map = &self.player_data.match_data_map;
-                                    {
-                                        if let Some(Some(md)) = map.get(&summary.match_id) {
-                                            let player_data =
-                                                |ui: &mut Ui, role_index: u8, name: &str| {
-                                                    ui.horizontal(|ui| {
-                                                        ui.label(
-                                                            UGG_ROLES_REVERSED[role_index as usize],
-                                                        );
-                                                        ui.label(name);
-                                                    });
-                                                };
-
-                                            ui.horizontal(|ui| {
-                                                ui.vertical(|ui| {
-                                                    for player in md.match_summary.team_a.iter() {
-                                                        player_data(
-                                                            ui,
-                                                            player.role,
-                                                            &player.riot_user_name,
-                                                        );
-                                                    }
-                                                });
-
-                                                ui.separator();
-
-                                                ui.vertical(|ui| {
-                                                    for player in md.match_summary.team_b.iter() {
-                                                        player_data(
-                                                            ui,
-                                                            player.role,
-                                                            &player.riot_user_name,
-                                                        );
-                                                    }
-                                                });
-                                            });
+
+                                    ui.horizontal(|ui| {
+                                        if summary.win {
+                                            ui.label(RichText::new("Win").color(Color32::BLUE))
+                                        } else {
+                                            ui.label(RichText::new("Loss").color(Color32::RED))
+                                        };
+                                        ui.label(format!(
+                                            "Hard Carry: {} Teamplay: {}",
+                                            summary.ps_hard_carry, summary.ps_team_play
+                                        ));
+                                    });
+
+                                    ui.label(format!(
+                                        "{:.1} CS/min  {:.0} Gold/min  {:.0} Damage/min  {:.2} Vision/min",
+                                        summary.cs_per_min(),
+                                        summary.gold_per_min(),
+                                        summary.damage_per_min(),
+                                        summary.vision_per_min()
+                                    ));
+
+                                    let duration = format_time(summary.match_duration);
+                                    match played_at {
+                                        Some(time) => {
+                                            ui.label(format!(
+                                                "{} game, {}",
+                                                duration,
+                                                time_ago(time, now)
+                                            ))
+                                            .on_hover_text(
+                                                time.format("%A %-d %B %Y, %H:%M").to_string(),
+                                            );
+                                        }
+                                        None => {
+                                            ui.label(format!("{} game", duration));
                                         }
                                     }
+
+                                    item_row(
+                                        ui,
+                                        self.shared_state,
+                                        ddragon_version(&versions, &summary.version),
+                                        &summary.items,
+                                        0.03 * height,
+                                    );
                                 });
-                                // ui.add_space(0.01 * height);
-                                ui.separator();
-                            }
+                            })
+                            .body(|ui| {
+                                let map = &self.tab.player_data.match_data_map;
+                                if let Some(Some(md)) = map.get(&summary.match_id) {
+                                    clicked_player = clicked_player.take().or(scoreboard(
+                                        ui,
+                                        self.shared_state,
+                                        &champs,
+                                        ddragon_version(&versions, &md.match_summary.version),
+                                        md,
+                                        (&self.tab.riot_user_name, &self.tab.riot_tag_line),
+                                        0.03 * height,
+                                    ));
+
+                                    ui.separator();
+
+                                    damage_chart(ui, &champs, md, 0.03 * height);
+                                } else {
+                                    // The full details are still loading, the summary has the rosters though
+                                    clicked_player = clicked_player.take().or(roster(ui, summary));
+                                }
+                            });
+                            // ui.add_space(0.01 * height);
+                            ui.separator();
                         }
                     }
-                });
+                }
+            });
+            if self.tab.view == ProfileView::Matches {
+                self.tab.scroll_offset = scroll.state.offset.y;
+            }
+
+            if let Some(champion) = clicked_champion {
+                self.tab.view = ProfileView::Matches;
+                self.set_champion_filter(Some(champion));
+            }
+
+            if let Some((name, tag_line)) = clicked_player {
+                self.open_profile(self.tab.region, name, tag_line);
+            }
         });
     }
 
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, FAVORITES_KEY, &self.favorites);
+
+        let settings = Settings {
+            region: self.tab.region.to_owned(),
+            role: self.tab.role,
+            locale: self.data_dragon.selected_locale.to_owned(),
+            use_sprites: self.data_dragon.use_sprites,
+            recent_searches: self.recent_searches.clone(),
+        };
+        eframe::set_value(storage, SETTINGS_KEY, &settings);
+    }
+
     fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
         egui::Rgba::TRANSPARENT.to_array()
     }
 }
 
-#[allow(unused)]
+/// The solo queue rank if the player has one, otherwise whatever queue they are ranked in
+pub fn rank_summary(ranks: &[RankScore]) -> Option<String> {
+    let rank = ranks
+        .iter()
+        .find(|rank| rank.queue_type == "ranked_solo_5x5")
+        .or(ranks.first())?;
+
+    Some(format!("{} {} {} LP", rank.tier, rank.rank, rank.lp))
+}
+
+fn favorites_panel(
+    ui: &mut Ui,
+    shared_state: &SharedState,
+    favorites: &[Favorite],
+    region_id_name: &HashMap<&'static str, &'static str>,
+) -> Option<FavoriteAction> {
+    let mut action = None;
+
+    ui.label(RichText::new("Favorites").strong());
+    ui.separator();
+
+    if favorites.is_empty() {
+        ui.label("No Favorites");
+        return None;
+    }
+
+    let icons = shared_state.player_icons.try_read().ok();
+    let size = Vec2::splat(2.0 * ui.text_style_height(&egui::TextStyle::Body));
+
+    egui::ScrollArea::vertical()
+        .id_source("Favorites Scroll")
+        .show(ui, |ui| {
+            for (index, favorite) in favorites.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    match icons.as_ref().and_then(|icons| icons.get(&favorite.icon_id)) {
+                        Some(texture) => {
+                            ui.add(Image::new(texture).max_size(size));
+                        }
+                        None => {
+                            ui.add_sized(size, egui::Spinner::new());
+                        }
+                    }
+
+                    ui.vertical(|ui| {
+                        let riot_id = format!(
+                            "{}#{}",
+                            favorite.riot_user_name, favorite.riot_tag_line
+                        );
+                        if ui.link(riot_id).clicked() {
+                            action = Some(FavoriteAction::Open(index));
+                        }
+
+                        let region = region_id_name
+                            .get(favorite.region.as_str())
+                            .copied()
+                            .unwrap_or(favorite.region.as_str());
+                        let rank = favorite.rank.as_deref().unwrap_or("Unranked");
+                        ui.label(format!("{} - {}", region, rank));
+                    });
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.small_button("⏶").clicked() {
+                        action = Some(FavoriteAction::MoveUp(index));
+                    }
+                    if ui.small_button("⏷").clicked() {
+                        action = Some(FavoriteAction::MoveDown(index));
+                    }
+                    if ui.small_button("✖").clicked() {
+                        action = Some(FavoriteAction::Remove(index));
+                    }
+                });
+
+                ui.separator();
+            }
+        });
+
+    action
+}
+
+/// Totals over the loaded matches, or every page of them, returns whether loading them should be retried
+fn profile_stats_panel(
+    ui: &mut Ui,
+    stats: Option<&ProfileStats>,
+    loading: bool,
+    error: Option<&str>,
+    all_pages: &mut bool,
+) -> bool {
+    let mut retry = false;
+
+    ui.vertical(|ui| {
+        ui.horizontal(|ui| {
+            ui.label("Stats");
+            ui.checkbox(all_pages, "All Pages");
+        });
+
+        if let Some(err) = error {
+            ui.label(RichText::new(format!("✖ {}", err)).color(Color32::RED));
+            retry = ui.button("Retry").clicked();
+            return;
+        }
+
+        if loading {
+            ui.spinner();
+            return;
+        }
+
+        let Some(stats) = stats else {
+            ui.label("No Matches");
+            return;
+        };
+
+        ui.label(format!(
+            "{}W {}L ({:.0}%) in {} games",
+            stats.wins,
+            stats.losses(),
+            stats.winrate(),
+            stats.games
+        ));
+        ui.label(format!(
+            "KDA: {:.1}/{:.1}/{:.1} ({:.2})",
+            stats.kills, stats.deaths, stats.assists, stats.kda
+        ));
+        ui.label(format!(
+            "CS/min: {:.1} Kill Participation: {:.0}%",
+            stats.cs_per_min, stats.kill_participation
+        ));
+        ui.label(format!(
+            "Vision: {:.1} ({:.2}/min)",
+            stats.vision_score, stats.vision_per_min
+        ));
+
+        form_strip(ui, &stats.form);
+    });
+
+    retry
+}
+
+/// One square per match, newest on the left
+fn form_strip(ui: &mut Ui, form: &[bool]) {
+    let size = Vec2::splat(0.8 * ui.text_style_height(&egui::TextStyle::Body));
+
+    ui.horizontal(|ui| {
+        ui.spacing_mut().item_spacing.x = 0.2 * size.x;
+        for win in form {
+            let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+            let color = if *win { Color32::BLUE } else { Color32::RED };
+            ui.painter().rect_filled(rect, 2.0, color);
+            response.on_hover_text(if *win { "Win" } else { "Loss" });
+        }
+    });
+}
+
+/// Sorting and filters for the loaded page of matches
+fn match_list_controls(
+    ui: &mut Ui,
+    champs: &HashMap<i64, Champ>,
+    summaries: &[MatchSummary],
+    options: &mut MatchListOptions,
+    (query, query_error): (&mut String, &mut Option<filter::ParseError>),
+) {
+    ui.horizontal(|ui| {
+        ui.label("Filter: ");
+
+        // The term that failed to parse is underlined, as long as it's still in the text
+        let error_span = query_error.as_ref().map(|err| err.span.clone());
+        let mut layouter = |ui: &Ui, text: &str, wrap_width: f32| {
+            let font_id = egui::TextStyle::Body.resolve(ui.style());
+            let normal = egui::TextFormat::simple(font_id.clone(), ui.visuals().text_color());
+            let error = egui::TextFormat {
+                color: Color32::RED,
+                underline: egui::Stroke::new(1.0, Color32::RED),
+                ..egui::TextFormat::simple(font_id, Color32::RED)
+            };
+
+            let mut job = egui::text::LayoutJob::default();
+            match error_span.clone().filter(|span| text.get(span.clone()).is_some()) {
+                Some(span) => {
+                    job.append(&text[..span.start], 0.0, normal.clone());
+                    job.append(&text[span.clone()], 0.0, error);
+                    job.append(&text[span.end..], 0.0, normal);
+                }
+                None => job.append(text, 0.0, normal),
+            }
+            job.wrap.max_width = wrap_width;
+            ui.fonts(|fonts| fonts.layout_job(job))
+        };
+
+        let search = ui.add(
+            TextEdit::singleline(query)
+                .hint_text("champ:Ahri role:mid win kda>3 patch:14.5 with:Name#TAG")
+                .desired_width(f32::INFINITY)
+                .layouter(&mut layouter),
+        );
+
+        if search.changed() {
+            // Matched against both the name and data dragon's key, so Wukong and MonkeyKing both work
+            let champion_id = |name: &str| {
+                let name = filter::normalize_name(name);
+                champs
+                    .iter()
+                    .find(|(_, champ)| {
+                        filter::normalize_name(&champ.name) == name
+                            || filter::normalize_name(&champ.key) == name
+                    })
+                    .map(|(id, _)| *id)
+            };
+
+            // A query that doesn't parse keeps the last one that did, until it's fixed
+            match Query::parse(query, champion_id) {
+                Ok(parsed) => {
+                    options.query = (!parsed.is_empty()).then_some(parsed);
+                    *query_error = None;
+                }
+                Err(err) => *query_error = Some(err),
+            }
+        }
+    });
+
+    if let Some(err) = query_error {
+        ui.label(
+            RichText::new(format!("✖ {}", err.message))
+                .small()
+                .color(Color32::RED),
+        );
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Sort: ");
+        let selected = MatchSort::ALL
+            .iter()
+            .find(|(sort, _)| *sort == options.sort)
+            .map_or("", |(_, name)| *name);
+        ComboBox::from_id_source("Match Sort")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                for (sort, name) in MatchSort::ALL {
+                    ui.selectable_value(&mut options.sort, sort, name);
+                }
+            });
+
+        let direction = if options.descending { "⏷" } else { "⏶" };
+        if ui
+            .button(direction)
+            .on_hover_text("Reverse the order")
+            .clicked()
+        {
+            options.descending = !options.descending;
+        }
+
+        ui.separator();
+
+        ui.selectable_value(&mut options.outcome, Outcome::All, "All");
+        ui.selectable_value(&mut options.outcome, Outcome::Wins, "Wins");
+        ui.selectable_value(&mut options.outcome, Outcome::Losses, "Losses");
+
+        ui.separator();
+
+        ui.checkbox(&mut options.hide_remakes, "Hide Remakes")
+            .on_hover_text(format!(
+                "Matches shorter than {} minutes",
+                filter::REMAKE_DURATION / 60
+            ));
+
+        ui.separator();
+
+        ui.label("Queue: ");
+        ComboBox::from_id_source("Match Queue")
+            .selected_text(options.queue.as_deref().map_or("Every Queue", filter::queue_name))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut options.queue, None, "Every Queue");
+                for queue in filter::queues(summaries) {
+                    ui.selectable_value(
+                        &mut options.queue,
+                        Some(queue.to_owned()),
+                        filter::queue_name(queue),
+                    );
+                }
+            });
+
+        if *options != MatchListOptions::default() {
+            let shown = summaries.iter().filter(|summary| options.matches(summary)).count();
+            ui.label(format!("Showing {} of {}", shown, summaries.len()));
+            if ui.button("Reset").clicked() {
+                *options = MatchListOptions::default();
+                query.clear();
+                *query_error = None;
+            }
+        }
+    });
+}
+
+/// Every champion the player has played, returns the one that was clicked
+fn champion_pool_table(
+    ui: &mut Ui,
+    champs: &HashMap<i64, Champ>,
+    mut pool: Vec<ChampionStats>,
+    (column, descending): (&mut ChampionColumn, &mut bool),
+    size: f32,
+) -> Option<i64> {
+    if pool.is_empty() {
+        ui.label("No Recent Matches");
+        return None;
+    }
+
+    pool.sort_by(|a, b| {
+        let order = match column {
+            // Names are reversed, so the default descending order is alphabetical
+            ChampionColumn::Champion => {
+                champ(champs, b.champion_id).name.cmp(&champ(champs, a.champion_id).name)
+            }
+            ChampionColumn::Games => a.record.games.cmp(&b.record.games),
+            ChampionColumn::Winrate => a.record.winrate().total_cmp(&b.record.winrate()),
+            ChampionColumn::Kda => a.record.kda().total_cmp(&b.record.kda()),
+            ChampionColumn::CsPerMin => a.cs_per_min.total_cmp(&b.cs_per_min),
+            ChampionColumn::LastPlayed => a.last_played.cmp(&b.last_played),
+        };
+        if *descending {
+            order.reverse()
+        } else {
+            order
+        }
+    });
+
+    let mut clicked = None;
+    let now = Local::now();
+
+    egui::Grid::new("Champion Pool")
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("");
+            let headers = [
+                (ChampionColumn::Champion, "Champion"),
+                (ChampionColumn::Games, "Games"),
+                (ChampionColumn::Winrate, "Winrate"),
+                (ChampionColumn::Kda, "KDA"),
+                (ChampionColumn::CsPerMin, "CS/min"),
+                (ChampionColumn::LastPlayed, "Last Played"),
+            ];
+            for (header, name) in headers {
+                let selected = *column == header;
+                let text = match (selected, *descending) {
+                    (true, true) => format!("{} ⏷", name),
+                    (true, false) => format!("{} ⏶", name),
+                    (false, _) => name.to_owned(),
+                };
+                if ui.selectable_label(selected, RichText::new(text).strong()).clicked() {
+                    // Clicking the sorted column again flips it
+                    *descending = !selected || !*descending;
+                    *column = header;
+                }
+            }
+            ui.end_row();
+
+            for stats in &pool {
+                let champ = champ(champs, stats.champion_id);
+                champ_image(ui, champ, size);
+                if ui
+                    .link(&champ.name)
+                    .on_hover_text(format!("Only show matches on {}", champ.name))
+                    .clicked()
+                {
+                    clicked = Some(stats.champion_id);
+                }
+                ui.label(stats.record.games.to_string());
+                ui.label(format!("{:.0}%", stats.record.winrate()));
+                ui.label(format_kda(&stats.record));
+                ui.label(format!("{:.1}", stats.cs_per_min));
+                match match_time(stats.last_played) {
+                    Some(time) => ui.label(time_ago(time, now)),
+                    None => ui.label(""),
+                };
+                ui.end_row();
+            }
+        });
+
+    clicked
+}
+
+/// How often each role is played, and how well, to make autofilled games stand out
+fn role_chart(ui: &mut Ui, roles: &[RoleStats]) {
+    let total: usize = roles.iter().map(|role| role.record.games).sum();
+    if total == 0 {
+        ui.label("No Recent Matches");
+        return;
+    }
+
+    let width = 0.2 * ui.ctx().screen_rect().width();
+
+    egui::Grid::new("Role Distribution")
+        .striped(true)
+        .show(ui, |ui| {
+            for header in ["Role", "Played", "Winrate", "KDA"] {
+                ui.label(RichText::new(header).strong());
+            }
+            ui.end_row();
+
+            for role in roles {
+                let record = &role.record;
+                let share = stats::percentage(record.games, total);
+
+                ui.label(role_name(role.role));
+                ui.add(
+                    egui::ProgressBar::new(share as f32 / 100.0)
+                        .desired_width(width)
+                        .text(format!("{} games ({:.0}%)", record.games, share)),
+                );
+
+                let color = if record.winrate() >= 50.0 {
+                    Color32::from_rgb(70, 110, 200)
+                } else {
+                    Color32::from_rgb(200, 70, 70)
+                };
+                ui.add(
+                    egui::ProgressBar::new(record.winrate() as f32 / 100.0)
+                        .desired_width(width)
+                        .fill(color)
+                        .text(format!(
+                            "{}W {}L ({:.0}%)",
+                            record.wins,
+                            record.losses(),
+                            record.winrate()
+                        )),
+                );
+                ui.label(format_kda(record));
+                ui.end_row();
+            }
+        });
+}
+
+/// The player's record against each lane opponent, with the worst ones listed first
+fn matchup_view(
+    ui: &mut Ui,
+    champs: &HashMap<i64, Champ>,
+    summaries: &[MatchSummary],
+    lane: &mut Option<(i64, i64)>,
+    size: f32,
+) {
+    let lanes = stats::played_lanes(summaries);
+    if lanes.is_empty() {
+        ui.label("No Lane Matchups");
+        return;
+    }
+
+    // The selection is kept per tab, but it might not have been played in these matches
+    if lane.is_some_and(|selected| !lanes.contains(&selected)) {
+        *lane = None;
+    }
+
+    let lane_name = |(champion, role): (i64, i64)| {
+        format!("{} {}", champ(champs, champion).name, role_name(role))
+    };
+
+    ui.horizontal(|ui| {
+        ui.label("Playing: ");
+        ComboBox::from_id_source("Matchup Lane")
+            .selected_text(lane.map_or_else(|| "Every Lane".to_owned(), lane_name))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(lane, None, "Every Lane");
+                for played in &lanes {
+                    ui.selectable_value(lane, Some(*played), lane_name(*played));
+                }
+            });
+    });
+
+    let matchups = stats::lane_matchups(summaries, *lane);
+
+    ui.add_space(size);
+    ui.label(RichText::new("Hardest Matchups").strong());
+    let hardest = stats::hardest_matchups(&matchups, 5);
+    if hardest.is_empty() {
+        ui.label(format!(
+            "No champion has been played against {} times yet",
+            stats::MIN_MATCHUP_GAMES
+        ));
+    }
+    for matchup in hardest {
+        ui.horizontal(|ui| {
+            let opponent = champ(champs, matchup.opponent_id);
+            champ_image(ui, opponent, size);
+            ui.label(format!(
+                "{} {:.0}% in {} games",
+                opponent.name,
+                matchup.record.winrate(),
+                matchup.record.games
+            ));
+        });
+    }
+
+    ui.add_space(size);
+
+    egui::Grid::new("Lane Matchups")
+        .striped(true)
+        .show(ui, |ui| {
+            for header in ["", "Against", "Games", "Winrate", "KDA"] {
+                ui.label(RichText::new(header).strong());
+            }
+            ui.end_row();
+
+            for matchup in &matchups {
+                let opponent = champ(champs, matchup.opponent_id);
+                champ_image(ui, opponent, size);
+                ui.label(&opponent.name);
+                ui.label(matchup.record.games.to_string());
+                ui.label(format!(
+                    "{}W {}L ({:.0}%)",
+                    matchup.record.wins,
+                    matchup.record.losses(),
+                    matchup.record.winrate()
+                ));
+                ui.label(format_kda(&matchup.record));
+                ui.end_row();
+            }
+        });
+}
+
+/// The players who are on the same team the most, returns the one that was clicked
+fn teammate_table(ui: &mut Ui, teammates: &[TeammateStats]) -> Option<(String, String)> {
+    if teammates.is_empty() {
+        ui.label("No Frequent Teammates");
+        return None;
+    }
+
+    let mut clicked = None;
+
+    egui::Grid::new("Frequent Teammates")
+        .striped(true)
+        .show(ui, |ui| {
+            for header in ["Player", "Games", "Winrate Together", "KDA", ""] {
+                ui.label(RichText::new(header).strong());
+            }
+            ui.end_row();
+
+            for teammate in teammates {
+                let riot_id = format!("{}#{}", teammate.riot_user_name, teammate.riot_tag_line);
+                if ui.link(riot_id).clicked() {
+                    clicked = Some((
+                        teammate.riot_user_name.clone(),
+                        teammate.riot_tag_line.clone(),
+                    ));
+                }
+
+                let record = &teammate.record;
+                ui.label(record.games.to_string());
+                ui.label(format!(
+                    "{}W {}L ({:.0}%)",
+                    record.wins,
+                    record.losses(),
+                    record.winrate()
+                ));
+                ui.label(format_kda(record));
+
+                if teammate.is_premade() {
+                    ui.label(RichText::new("Premade").strong().color(Color32::GOLD))
+                        .on_hover_text(format!(
+                            "{} games in a row together",
+                            teammate.longest_streak
+                        ));
+                } else {
+                    ui.label("");
+                }
+                ui.end_row();
+            }
+        });
+
+    clicked
+}
+
+/// Matches grouped by the breaks between them, with a warning when a player should stop queueing
+fn session_list(ui: &mut Ui, summaries: &[MatchSummary]) {
+    let sessions = stats::sessions(summaries);
+    if sessions.is_empty() {
+        ui.label("No Recent Matches");
+        return;
+    }
+
+    let streak = stats::current_loss_streak(summaries);
+    if streak >= stats::TILT_STREAK {
+        ui.label(
+            RichText::new(format!(
+                "⚠ Lost the last {} games in a row, it might be time for a break",
+                streak
+            ))
+            .strong()
+            .color(Color32::RED),
+        );
+        ui.separator();
+    }
+
+    let now = Local::now();
+
+    egui::Grid::new("Sessions")
+        .striped(true)
+        .show(ui, |ui| {
+            for header in ["Played", "Length", "Games", "Record", "LP", ""] {
+                ui.label(RichText::new(header).strong());
+            }
+            ui.end_row();
+
+            for session in &sessions {
+                match (match_time(session.start), match_time(session.end)) {
+                    (Some(start), Some(end)) => {
+                        ui.label(format!(
+                            "{} {} - {}",
+                            day_heading(start.date_naive(), now),
+                            start.format("%H:%M"),
+                            end.format("%H:%M")
+                        ));
+                    }
+                    _ => {
+                        ui.label("");
+                    }
+                }
+                ui.label(format_time((session.end - session.start) / 1000));
+
+                let record = &session.record;
+                ui.label(record.games.to_string());
+                ui.label(format!(
+                    "{}W {}L ({:.0}%)",
+                    record.wins,
+                    record.losses(),
+                    record.winrate()
+                ));
+
+                match session.lp {
+                    Some(lp) => {
+                        let color = if lp >= 0 { Color32::BLUE } else { Color32::RED };
+                        ui.label(RichText::new(format!("{:+} LP", lp)).color(color));
+                    }
+                    None => {
+                        ui.label("Unranked");
+                    }
+                }
+
+                if session.tilted() {
+                    ui.label(RichText::new("Tilt").strong().color(Color32::RED))
+                        .on_hover_text(format!(
+                            "Lost {} games in a row",
+                            session.longest_loss_streak
+                        ));
+                } else {
+                    ui.label("");
+                }
+                ui.end_row();
+            }
+        });
+}
+
+/// Games played and winrate by day and hour, the more games the brighter the cell
+fn heatmap(ui: &mut Ui, heatmap: &Heatmap, size: f32) {
+    const DAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+    let most = heatmap
+        .iter()
+        .flatten()
+        .map(|record| record.games)
+        .max()
+        .unwrap_or_default();
+    if most == 0 {
+        ui.label("No Recent Matches");
+        return;
+    }
+
+    ui.label("Times are in your local timezone, blue is a winning hour and red a losing one");
+    ui.add_space(size / 2.0);
+
+    let cell = Vec2::splat(size);
+    let label_width = 3.0 * size;
+
+    ui.spacing_mut().item_spacing = Vec2::splat(0.1 * size);
+
+    ui.horizontal(|ui| {
+        ui.add_space(label_width);
+        for hour in 0..24 {
+            let (rect, _) = ui.allocate_exact_size(cell, egui::Sense::hover());
+            if hour % 3 == 0 {
+                ui.painter().text(
+                    rect.left_center(),
+                    egui::Align2::LEFT_CENTER,
+                    format!("{:02}", hour),
+                    egui::FontId::proportional(0.6 * size),
+                    ui.visuals().text_color(),
+                );
+            }
+        }
+    });
+
+    for (day, hours) in DAYS.iter().zip(heatmap) {
+        ui.horizontal(|ui| {
+            ui.add_sized(Vec2::new(label_width, size), Label::new(*day));
+
+            for (hour, record) in hours.iter().enumerate() {
+                let (rect, response) = ui.allocate_exact_size(cell, egui::Sense::hover());
+
+                let color = if record.games == 0 {
+                    ui.visuals().faint_bg_color
+                } else {
+                    // Anything played at all is still visible, no matter how rarely
+                    let intensity = 0.25 + 0.75 * record.games as f32 / most as f32;
+                    let base = if record.winrate() >= 50.0 {
+                        Color32::from_rgb(70, 110, 200)
+                    } else {
+                        Color32::from_rgb(200, 70, 70)
+                    };
+                    base.gamma_multiply(intensity)
+                };
+                ui.painter().rect_filled(rect, 2.0, color);
+
+                response.on_hover_text(format!(
+                    "{} {:02}:00 - {:02}:00\n{} games, {}W {}L ({:.0}%)",
+                    day,
+                    hour,
+                    (hour + 1) % 24,
+                    record.games,
+                    record.wins,
+                    record.losses(),
+                    record.winrate()
+                ));
+            }
+        });
+    }
+}
+
+/// The record on every patch, and which champions came and went with it
+fn patch_list(ui: &mut Ui, champs: &HashMap<i64, Champ>, patches: &[PatchStats], size: f32) {
+    if patches.is_empty() {
+        ui.label("No Recent Matches");
+        return;
+    }
+
+    let portraits = |ui: &mut Ui, label: &str, ids: &[i64]| {
+        if ids.is_empty() {
+            return;
+        }
+        ui.horizontal_wrapped(|ui| {
+            ui.label(label);
+            for id in ids {
+                let champ = champ(champs, *id);
+                champ_image(ui, champ, size);
+                ui.label(&champ.name);
+            }
+        });
+    };
+
+    for stats in patches {
+        let (major, minor) = stats.patch;
+        let record = &stats.record;
+
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(format!("Patch {}.{}", major, minor)).strong());
+            ui.label(format!(
+                "{} games, {}W {}L ({:.0}%)",
+                record.games,
+                record.wins,
+                record.losses(),
+                record.winrate()
+            ));
+            ui.label(format!("KDA: {}", format_kda(record)));
+        });
+
+        ui.horizontal_wrapped(|ui| {
+            for (id, games) in &stats.champions {
+                let champ = champ(champs, *id);
+                champ_image(ui, champ, size);
+                ui.label(format!("{} ({})", champ.name, games));
+            }
+        });
+
+        portraits(ui, "Picked Up:", &stats.picked_up);
+        portraits(ui, "Dropped:", &stats.dropped);
+
+        ui.separator();
+    }
+}
+
+/// The average kills, deaths and assists, followed by the ratio over every game
+fn format_kda(record: &Record) -> String {
+    let (kills, deaths, assists) = record.average_kda();
+    format!("{:.1}/{:.1}/{:.1} ({:.2})", kills, deaths, assists, record.kda())
+}
+
+/// u.gg's role ids, anything unknown is shown as no role
+fn role_name(role: i64) -> &'static str {
+    usize::try_from(role)
+        .ok()
+        .and_then(|role| UGG_ROLES_REVERSED.get(role))
+        .copied()
+        .filter(|name| !name.is_empty())
+        .unwrap_or("None")
+}
+
+fn tab_bar(ui: &mut Ui, active: &Tab, tabs: &[Tab], active_index: usize) -> Option<TabAction> {
+    let mut action = None;
+
+    ui.horizontal_wrapped(|ui| {
+        for (index, tab) in tabs.iter().enumerate() {
+            // The active tab's slot is empty, it's been moved out to be shown
+            let is_active = index == active_index;
+            let title = if is_active { active.title() } else { tab.title() };
+
+            if ui.selectable_label(is_active, title).clicked() {
+                action = Some(TabAction::Switch(index));
+            }
+            if tabs.len() > 1 && ui.small_button("✖").clicked() {
+                action = Some(TabAction::Close(index));
+            }
+            ui.separator();
+        }
+
+        if ui.button("+").on_hover_text("New Tab").clicked() {
+            action = Some(TabAction::New);
+        }
+    });
+
+    action
+}
+
+fn champ_image(ui: &mut Ui, champ: &Champ, size: f32) {
+    if let Ok(image) = &champ.image.try_read() {
+        if let Some(texture) = &**image {
+            ui.add(Image::new(texture).max_size(Vec2::splat(size)));
+        } else {
+            ui.spinner();
+        }
+    } else {
+        ui.spinner();
+    }
+}
+
+/// The stats of all ten players in a match, with the searched player highlighted
+fn scoreboard(
+    ui: &mut Ui,
+    shared_state: &SharedState,
+    champs: &HashMap<i64, Champ>,
+    version: &str,
+    md: &Match,
+    (name, tag_line): (&str, &str),
+    size: f32,
+) -> Option<(String, String)> {
+    let mut clicked = None;
+
+    // These are only names, so the ids are shown until they're loaded
+    let lookup = |names: Option<&HashMap<i64, String>>, id: i64| {
+        names
+            .and_then(|names| names.get(&id))
+            .map_or_else(|| id.to_string(), Clone::clone)
+    };
+    let spells = shared_state.summoner_spells.get();
+    let runes = shared_state.runes.get();
+
+    let summary = &md.match_summary;
+    for (index, team) in [&summary.team_a, &summary.team_b].into_iter().enumerate() {
+        if team.first().is_some_and(|player| player.team_id == md.winning_team) {
+            ui.label(RichText::new("Victory").color(Color32::BLUE));
+        } else {
+            ui.label(RichText::new("Defeat").color(Color32::RED));
+        }
+
+        egui::Grid::new(("scoreboard", summary.match_id, index))
+            .striped(true)
+            .show(ui, |ui| {
+                let headers = [
+                    "", "Role", "Player", "KDA", "CS", "Gold", "Damage", "Items", "Spells", "Runes",
+                    "Hard Carry", "Teamplay",
+                ];
+                for header in headers {
+                    ui.label(RichText::new(header).strong());
+                }
+                ui.end_row();
+
+                for player in team.iter() {
+                    let searched = player.riot_user_name.eq_ignore_ascii_case(name)
+                        && player.riot_tag_line.eq_ignore_ascii_case(tag_line);
+                    let text = |text: String| {
+                        if searched {
+                            RichText::new(text).strong().color(Color32::GOLD)
+                        } else {
+                            RichText::new(text)
+                        }
+                    };
+
+                    champ_image(ui, champ(champs, player.champion_id), size);
+                    ui.label(text(UGG_ROLES_REVERSED[player.role as usize].to_owned()));
+                    let riot_id = format!("{}#{}", player.riot_user_name, player.riot_tag_line);
+                    if ui.link(text(riot_id)).clicked() {
+                        clicked = Some((
+                            player.riot_user_name.clone(),
+                            player.riot_tag_line.clone(),
+                        ));
+                    }
+                    // Anything missing from the post game data is left blank
+                    let post_game = player.post_game.as_ref();
+                    let stat = |stat: fn(&PostGameData) -> Option<i64>| post_game.and_then(stat);
+                    let list = |list: fn(&PostGameData) -> Option<&[i64]>| {
+                        post_game.and_then(list).unwrap_or_default()
+                    };
+
+                    let kills = stat(|data| data.kills);
+                    let deaths = stat(|data| data.deaths);
+                    let kda = match (kills, deaths, stat(|data| data.assists)) {
+                        (Some(kills), Some(deaths), Some(assists)) => {
+                            format!("{}/{}/{}", kills, deaths, assists)
+                        }
+                        _ => String::new(),
+                    };
+                    ui.label(text(kda));
+                    let cs = stat(|data| data.cs).zip(stat(|data| data.jungle_cs));
+                    ui.label(text(cs.map_or_else(String::new, |(cs, jungle_cs)| {
+                        (cs + jungle_cs).to_string()
+                    })));
+                    ui.label(text(
+                        stat(|data| data.gold).map_or_else(String::new, |gold| gold.to_string()),
+                    ));
+                    ui.label(text(player.damage.to_string()));
+                    item_row(ui, shared_state, version, list(|data| data.items.as_deref()), size);
+                    ui.label(text(
+                        list(|data| data.summoner_spells.as_deref())
+                            .iter()
+                            .map(|spell| lookup(spells, *spell))
+                            .collect::<Vec<_>>()
+                            .join(" / "),
+                    ));
+                    // The keystone is always the first rune
+                    let keystone = list(|data| data.runes.as_deref())
+                        .first()
+                        .map(|rune| lookup(runes, *rune));
+                    let sub_style = stat(|data| data.sub_style).map(|style| lookup(runes, style));
+                    let rune_text = match (keystone, sub_style) {
+                        (Some(keystone), Some(sub_style)) => format!("{} / {}", keystone, sub_style),
+                        (keystone, sub_style) => keystone.or(sub_style).unwrap_or_default(),
+                    };
+                    ui.label(text(rune_text));
+
+                    let score = md.performance_score.iter().find(|score| {
+                        score.riot_user_name.as_deref() == Some(&player.riot_user_name)
+                            && score.riot_tag_line.as_deref() == Some(&player.riot_tag_line)
+                    });
+                    // Both cells are still added without a score, so the columns stay lined up
+                    match score {
+                        Some(score) => {
+                            ui.label(text(score.hard_carry.to_string()));
+                            ui.label(text(score.teamplay.to_string()));
+                        }
+                        None => {
+                            ui.label("");
+                            ui.label("");
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+
+    clicked
+}
+
+/// Both teams from a match summary, returns the player that was clicked
+fn roster(ui: &mut Ui, summary: &MatchSummary) -> Option<(String, String)> {
+    let mut clicked = None;
+
+    ui.horizontal(|ui| {
+        for (index, team) in [&summary.team_a, &summary.team_b].into_iter().enumerate() {
+            if index != 0 {
+                ui.separator();
+            }
+
+            ui.vertical(|ui| {
+                for player in team.iter() {
+                    ui.horizontal(|ui| {
+                        ui.label(UGG_ROLES_REVERSED[player.role as usize]);
+                        let riot_id = format!("{}#{}", player.riot_user_name, player.riot_tag_line);
+                        if ui.link(riot_id).clicked() {
+                            clicked = Some((
+                                player.riot_user_name.clone(),
+                                player.riot_tag_line.clone(),
+                            ));
+                        }
+                    });
+                }
+            });
+        }
+    });
+
+    clicked
+}
+
+/// Damage dealt by every player, the bars are scaled to the highest damage in the match
+fn damage_chart(ui: &mut Ui, champs: &HashMap<i64, Champ>, md: &Match, size: f32) {
+    let summary = &md.match_summary;
+    let teams = [
+        (&summary.team_a, Color32::from_rgb(70, 110, 200)),
+        (&summary.team_b, Color32::from_rgb(200, 70, 70)),
+    ];
+    let most = summary
+        .team_a
+        .iter()
+        .chain(summary.team_b.iter())
+        .map(|player| player.damage)
+        .max()
+        .unwrap_or_default()
+        .max(1);
+
+    ui.horizontal_top(|ui| {
+        for (team, color) in teams {
+            let total: i64 = team.iter().map(|player| player.damage).sum();
+
+            ui.vertical(|ui| {
+                ui.label(RichText::new(format!("Team Damage: {}", total)).strong());
+
+                for player in team.iter() {
+                    let share = player.damage as f32 / total.max(1) as f32;
+                    ui.horizontal(|ui| {
+                        champ_image(ui, champ(champs, player.champion_id), size);
+                        ui.add(
+                            egui::ProgressBar::new(player.damage as f32 / most as f32)
+                                .desired_width(0.2 * ui.ctx().screen_rect().width())
+                                .fill(color)
+                                .text(format!(
+                                    "{} {} ({:.0}%)",
+                                    player.riot_user_name,
+                                    player.damage,
+                                    share * 100.0
+                                )),
+                        );
+                    });
+                }
+            });
+        }
+    });
+}
+
+/// MVP goes to the best performance on the winning team, ACE to the best on the losing team
+fn badge(summary: &MatchSummary) -> Option<&'static str> {
+    let score = |player: &Team| player.hard_carry + player.teamplay;
+    let is_player = |player: &Team| {
+        player
+            .riot_user_name
+            .eq_ignore_ascii_case(&summary.riot_user_name)
+            && player
+                .riot_tag_line
+                .eq_ignore_ascii_case(&summary.riot_tag_line)
+    };
+
+    let team = if summary.team_a.iter().any(is_player) {
+        &summary.team_a
+    } else {
+        &summary.team_b
+    };
+    let best = team
+        .iter()
+        .max_by(|a, b| score(a).total_cmp(&score(b)))?;
+
+    match (is_player(best), summary.win) {
+        (true, true) => Some("MVP"),
+        (true, false) => Some("ACE"),
+        (false, _) => None,
+    }
+}
+
+fn item_row(ui: &mut Ui, shared_state: &SharedState, version: &str, items: &[i64], size: f32) {
+    ui.horizontal(|ui| {
+        let Ok(map) = shared_state.item_icons.try_read() else {
+            ui.spinner();
+            return;
+        };
+        let icons = map.get(version);
+
+        // Empty slots are sent as 0
+        for item in items.iter().filter(|item| **item != 0) {
+            match icons.and_then(|icons| icons.get(item)) {
+                Some(Some(texture)) => {
+                    ui.add(Image::new(texture).max_size(Vec2::splat(size)));
+                }
+                _ => {
+                    ui.spinner();
+                }
+            }
+        }
+    });
+}
+
+/// Finds the data dragon version for the patch a match was played on, falling back to the latest
+///
+/// u.gg sends game versions like `14.5.565.5505` or `14_5`, while data dragon uses `14.5.1`
+pub fn ddragon_version<'a>(versions: &'a [String], match_version: &str) -> &'a str {
+    stats::patch(match_version)
+        .and_then(|target| {
+            versions
+                .iter()
+                .find(|version| stats::patch(version) == Some(target))
+        })
+        .unwrap_or(&versions[0])
+}
+
+/// When a match was played in local time, u.gg sends this as milliseconds since the epoch
+fn match_time(match_creation_time: i64) -> Option<DateTime<Local>> {
+    Local.timestamp_millis_opt(match_creation_time).single()
+}
+
+fn time_ago(time: DateTime<Local>, now: DateTime<Local>) -> String {
+    let elapsed = now.signed_duration_since(time);
+    let plural = |amount: i64, unit: &str| {
+        if amount == 1 {
+            format!("1 {} ago", unit)
+        } else {
+            format!("{} {}s ago", amount, unit)
+        }
+    };
+
+    if elapsed.num_minutes() < 1 {
+        "just now".to_owned()
+    } else if elapsed.num_hours() < 1 {
+        plural(elapsed.num_minutes(), "minute")
+    } else if elapsed.num_days() < 1 {
+        plural(elapsed.num_hours(), "hour")
+    } else if elapsed.num_days() < 30 {
+        plural(elapsed.num_days(), "day")
+    } else {
+        plural(elapsed.num_days() / 30, "month")
+    }
+}
+
+/// The heading shown above the first match of every day
+fn day_heading(day: NaiveDate, now: DateTime<Local>) -> String {
+    let today = now.date_naive();
+    if day == today {
+        "Today".to_owned()
+    } else if today.pred_opt() == Some(day) {
+        "Yesterday".to_owned()
+    } else {
+        day.format("%A %-d %B %Y").to_string()
+    }
+}
+
+/// Formats a match's length in seconds, the hours are left out for anything under an hour
 fn format_time(match_time: i64) -> String {
     let native_time = NaiveDateTime::from_timestamp_opt(match_time, 0).unwrap();
     let time: DateTime<Utc> = DateTime::from_local(native_time, Utc);
@@ -1,6 +1,11 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{btree_map, hash_map::Entry, HashMap},
+    sync::{atomic::Ordering, Arc},
+};
 
 use crate::{
+    stats,
+    structs::{MatchSummary, ProfileInfo, RankScore},
     ui::{self, Champ, Payload, Results},
 };
 
@@ -15,151 +20,481 @@ const fn get_role_index(role: u8) -> Option<u8> {
     }
 }
 
+/// The player's champion in every match, and their lane opponent's for the matchups
+fn summary_champions(summaries: &[MatchSummary]) -> impl Iterator<Item = i64> + '_ {
+    summaries.iter().flat_map(|summary| {
+        let opponent = stats::lane_opponent(summary).map(|opponent| opponent.champion_id);
+        std::iter::once(summary.champion_id).chain(opponent)
+    })
+}
+
 impl ui::MyEguiApp {
     pub fn send_message(&self, payload: Payload) {
         self.messenger.try_send(payload).unwrap();
     }
 
-    pub fn update_matches(&self, name: &Arc<String>, tag_line: &Arc<String>) {
+    /// Reloads the profile in the active tab, anything still loading for it is dropped when it arrives
+    pub fn update_matches(&mut self) {
+        self.navigate();
+        let request = self.profile_request();
+        let name = &self.tab.riot_user_name;
+        let tag_line = &self.tab.riot_tag_line;
+
         // These need to be disabled for now
         self.send_message(Payload::MatchSummaries {
+            tab: request,
             name: name.clone(),
             tag_line: tag_line.clone(),
-            roles: get_role_index(self.role),
-            region_id: self.data_dragon.region,
-            page: self.page,
+            roles: get_role_index(self.tab.role),
+            champion: self.tab.champion.map(|id| id as i16),
+            region_id: self.tab.region,
+            page: self.tab.page,
         });
         self.send_message(Payload::PlayerRanking {
+            tab: request,
             name: name.clone(),
             tag_line: tag_line.clone(),
-            region_id: self.data_dragon.region,
+            region_id: self.tab.region,
         });
         // self.send_message(Payload::PlayerRanking {
         //     name: name.clone(),
-        //     region_id: self.data_dragon.region,
+        //     region_id: self.tab.region,
         // });
         self.send_message(Payload::PlayerInfo {
+            tab: Some(request),
             name: name.clone(),
             tag_line: tag_line.clone(),
             version_index: 0,
-            region_id: self.data_dragon.region,
+            region_id: self.tab.region,
         });
     }
 
+    pub fn profile_request(&self) -> ui::ProfileRequest {
+        ui::ProfileRequest {
+            tab: self.tab.id,
+            navigation: self.tab.navigation,
+        }
+    }
+
+    fn navigate(&mut self) {
+        self.tab.navigation += 1;
+    }
+
+    /// Only matches on `champion` are loaded from now on, starting over from the first page
+    pub fn set_champion_filter(&mut self, champion: Option<i64>) {
+        self.tab.champion = champion;
+        self.tab.page = 1;
+        self.tab.player_data.reset_all_pages();
+
+        if !self.tab.riot_user_name.is_empty() {
+            self.update_matches();
+        }
+    }
+
+    /// Only matches in `role` are loaded from now on, starting over from the first page
+    pub fn set_role_filter(&mut self, role: u8) {
+        self.tab.role = role;
+        self.tab.page = 1;
+        self.tab.player_data.reset_all_pages();
+
+        if !self.tab.riot_user_name.is_empty() {
+            self.update_matches();
+        }
+    }
+
+    /// Fetches every page of matches for the stats panel, once per profile
+    pub fn load_all_pages(&mut self) {
+        if !self.tab.stats_all_pages
+            || self.tab.player_data.all_pages_started
+            || self.tab.player_data.all_pages_error.is_some()
+            || self.tab.riot_user_name.is_empty()
+        {
+            return;
+        }
+
+        self.send_message(Payload::AllMatchSummaries {
+            tab: ui::ProfileRequest {
+                tab: self.tab.id,
+                navigation: self.tab.player_data.all_pages_navigation,
+            },
+            name: self.tab.riot_user_name.clone(),
+            tag_line: self.tab.riot_tag_line.clone(),
+            roles: get_role_index(self.tab.role),
+            champion: self.tab.champion.map(|id| id as i16),
+            region_id: self.tab.region,
+        });
+        self.tab.player_data.all_pages_started = true;
+    }
+
+    fn load_items(&self, version: &str, items: &[i64]) {
+        let mut map = self.shared_state.item_icons.write().unwrap();
+        let icons = map.entry(version.to_owned()).or_default();
+
+        for &id in items.iter().filter(|item| **item != 0) {
+            if let btree_map::Entry::Vacant(entry) = icons.entry(id) {
+                entry.insert(None);
+                self.send_message(Payload::GetItemImage {
+                    version: version.to_owned(),
+                    id,
+                });
+            }
+        }
+    }
+
+    fn load_champ_images(&mut self, champs: &HashMap<i64, Champ>, ids: impl Iterator<Item = i64>) {
+        for id in ids {
+            let Some(champ) = champs.get(&id) else {
+                // Every summary with this champ ends up here, so it's only reported once
+                if self.data_dragon.missing_champs.insert(id) {
+                    println!("Champ {} is missing from champion.json", id);
+                }
+                if !self.data_dragon.champ_refresh_started {
+                    self.send_message(Payload::RefreshChampInfo {
+                        locale: self.data_dragon.locale,
+                    });
+                    self.data_dragon.champ_refresh_started = true;
+                }
+                continue;
+            };
+
+            if self.data_dragon.use_sprites || champ.image_started.load(Ordering::Relaxed) {
+                continue;
+            }
+
+            self.send_message(Payload::GetChampImage {
+                version: champ.version.clone(),
+                key: champ.key.clone(),
+                id,
+            });
+            champ.image_started.store(true, Ordering::Relaxed);
+        }
+    }
+
+    fn take_snapshot(&mut self) -> ui::ProfileSnapshot {
+        ui::ProfileSnapshot {
+            riot_user_name: self.tab.riot_user_name.clone(),
+            riot_tag_line: self.tab.riot_tag_line.clone(),
+            region: self.tab.region,
+            role: self.tab.role,
+            champion: self.tab.champion,
+            page: self.tab.page,
+            finished_match_summaries: self.tab.finished_match_summaries,
+            scroll_offset: self.tab.scroll_offset,
+            match_summaries: self.tab.player_data.match_summaries.take(),
+            rank_scores: self.tab.player_data.rank_scores.take(),
+            ranking: self.tab.player_data.ranking.take(),
+            icon_id: std::mem::replace(&mut self.tab.player_data.icon_id, -1),
+        }
+    }
+
+    fn restore_snapshot(&mut self, snapshot: ui::ProfileSnapshot) {
+        self.tab.active_player = format!("{}#{}", snapshot.riot_user_name, snapshot.riot_tag_line);
+        self.tab.riot_user_name = snapshot.riot_user_name;
+        self.tab.riot_tag_line = snapshot.riot_tag_line;
+        self.tab.region = snapshot.region;
+        self.tab.role = snapshot.role;
+        self.tab.champion = snapshot.champion;
+        self.tab.page = snapshot.page;
+        self.tab.finished_match_summaries = snapshot.finished_match_summaries;
+        self.tab.pending_scroll = Some(snapshot.scroll_offset);
+        self.tab.player_data.match_summaries = snapshot.match_summaries;
+        self.tab.player_data.rank_scores = snapshot.rank_scores;
+        self.tab.player_data.ranking = snapshot.ranking;
+        self.tab.player_data.icon_id = snapshot.icon_id;
+        self.tab.player_data.reset_all_pages();
+        self.navigate();
+    }
+
+    /// Only profiles that have actually loaded are worth going back to
+    fn profile_loaded(&self) -> bool {
+        self.tab.player_data.match_summaries.is_some() || self.tab.player_data.rank_scores.is_some()
+    }
+
+    pub fn push_history(&mut self) {
+        if self.profile_loaded() {
+            let snapshot = self.take_snapshot();
+            self.tab.history.back.push(snapshot);
+            self.tab.history.forward.clear();
+        }
+    }
+
+    pub fn go_back(&mut self) {
+        if let Some(previous) = self.tab.history.back.pop() {
+            if self.profile_loaded() {
+                let current = self.take_snapshot();
+                self.tab.history.forward.push(current);
+            }
+            self.restore_snapshot(previous);
+        }
+    }
+
+    pub fn go_forward(&mut self) {
+        if let Some(next) = self.tab.history.forward.pop() {
+            if self.profile_loaded() {
+                let current = self.take_snapshot();
+                self.tab.history.back.push(current);
+            }
+            self.restore_snapshot(next);
+        }
+    }
+
+    /// Where a background tab is in `tabs`, the active tab's slot is never matched
+    pub fn tab_index(&self, id: ui::TabId) -> Option<usize> {
+        self.tabs
+            .iter()
+            .enumerate()
+            .position(|(index, tab)| index != self.active_tab && tab.id == id)
+    }
+
+    pub fn switch_tab(&mut self, index: usize) {
+        if index == self.active_tab || index >= self.tabs.len() {
+            return;
+        }
+
+        // The active tab goes back into its slot, and the new one takes its place
+        std::mem::swap(&mut self.tab, &mut self.tabs[self.active_tab]);
+        std::mem::swap(&mut self.tab, &mut self.tabs[index]);
+        self.active_tab = index;
+        self.tab.pending_scroll = Some(self.tab.scroll_offset);
+    }
+
+    /// Opens an empty tab in the current region and switches to it
+    pub fn new_tab(&mut self) {
+        self.tabs.push(ui::Tab::new(self.next_tab_id, self.tab.region));
+        self.next_tab_id += 1;
+        self.switch_tab(self.tabs.len() - 1);
+    }
+
+    /// There is always at least one tab open
+    pub fn close_tab(&mut self, index: usize) {
+        if self.tabs.len() == 1 || index >= self.tabs.len() {
+            return;
+        }
+
+        if index == self.active_tab {
+            // Whichever tab ends up next to it is shown instead
+            let next = if index + 1 < self.tabs.len() { index + 1 } else { index - 1 };
+            self.switch_tab(next);
+        }
+
+        self.tabs.remove(index);
+        if index < self.active_tab {
+            self.active_tab -= 1;
+        }
+    }
+
+    /// Keeps the icon and rank shown in the favorites panel up to date
+    fn update_favorite(&mut self, region: &str, info: &ProfileInfo, ranks: Option<&[RankScore]>) {
+        let favorite = self.favorites.iter_mut().find(|favorite| {
+            favorite.region == region
+                && favorite
+                    .riot_user_name
+                    .eq_ignore_ascii_case(&info.riot_user_name)
+                && favorite
+                    .riot_tag_line
+                    .eq_ignore_ascii_case(&info.riot_tag_line)
+        });
+
+        if let Some(favorite) = favorite {
+            favorite.icon_id = info.icon_id;
+            favorite.rank = ranks.and_then(ui::rank_summary);
+        }
+    }
+
+    /// Refreshes the icon and rank of every favorite, this is only done once on startup
+    pub fn load_favorites(&mut self) {
+        if self.data_dragon.favorites_started {
+            return;
+        }
+
+        for favorite in &self.favorites {
+            let Some((region_id, _)) = self
+                .data_dragon
+                .region_id_name
+                .get_key_value(favorite.region.as_str())
+            else {
+                continue;
+            };
+
+            self.send_message(Payload::PlayerInfo {
+                tab: None,
+                name: Arc::new(favorite.riot_user_name.clone()),
+                tag_line: Arc::new(favorite.riot_tag_line.clone()),
+                version_index: 0,
+                region_id,
+            });
+        }
+
+        self.data_dragon.favorites_started = true;
+    }
+
     pub fn update_data(&mut self, versions: &[String], champs: &HashMap<i64, Champ>) {
-        if let Ok(receiver) = self.receiver.try_recv() {
-            match receiver {
-                Results::MatchSum(match_sums) => match match_sums {
-                    Ok(matches) => {
-                        let data = matches.data.fetch_player_match_summaries;
-                        self.finished_match_summaries = data.match_summaries.len() != 20;
-                        let mut summaries = data.match_summaries;
-                        summaries.iter_mut().for_each(|summary| {
-                            if self.player_data.match_data_map.get(&summary.match_id).is_none() {
-                                self.player_data.match_data_map.insert(summary.match_id, None);
-                                self.send_message(Payload::GetMatchDetails { name: self.riot_user_name.clone(), tag_line: self.riot_tag_line.clone(), version: summary.version.clone(), id: summary.match_id, region_id: self.data_dragon.region });
-                            }
-
-                            let champ = &champs[&summary.champion_id];
-                            if !champ.image_started.load(std::sync::atomic::Ordering::Relaxed) {
-                                let key = &champ.key;
-                                self.send_message(
-                                    Payload::GetChampImage {
-                                        url: format!(
-                                            "https://ddragon.leagueoflegends.com/cdn/{}/img/champion/{}.png",
-                                            versions[0], key
-                                        ),
-                                        id: summary.champion_id,
-                                    },
-                                );
-                                champ.image_started.store(true, std::sync::atomic::Ordering::Relaxed);
-                            }
-                        });
-                        self.player_data.match_summaries = Some(summaries)
-                    }
-                    Err(err) => {
-                        dbg!("{:?}", err);
-                    }
-                },
-                Results::PlayerUpdate(update) => match update {
-                    Ok(updated) => {
-                        let data = updated.data.update_player_profile;
-                        if data.success {
-                            self.update_matches(&self.riot_user_name, &self.riot_tag_line);
-                        } else {
-                            dbg!("{:?}", data.error_reason);
+        if let Ok(result) = self.receiver.try_recv() {
+            match result.tab() {
+                // Results for a background tab are applied to it as if it was the active one
+                Some(id) if id != self.tab.id => {
+                    let Some(index) = self.tab_index(id) else {
+                        // The tab was closed while this was loading
+                        return;
+                    };
+                    std::mem::swap(&mut self.tab, &mut self.tabs[index]);
+                    self.handle_result(result, versions, champs);
+                    std::mem::swap(&mut self.tab, &mut self.tabs[index]);
+                }
+                _ => self.handle_result(result, versions, champs),
+            }
+        }
+    }
+
+    fn handle_result(&mut self, result: Results, versions: &[String], champs: &HashMap<i64, Champ>) {
+        match result {
+            // Any of these can arrive after the tab has navigated somewhere else
+            Results::MatchSum(request, _)
+            | Results::PlayerUpdate(request, _)
+            | Results::Ranking(request, _)
+                if request.navigation != self.tab.navigation => {}
+            Results::AllMatchSum(request, _)
+                if request.navigation != self.tab.player_data.all_pages_navigation => {}
+            Results::MatchSum(_, match_sums) => match match_sums {
+                Ok(matches) => {
+                    let data = matches.data.fetch_player_match_summaries;
+                    self.tab.finished_match_summaries = data.match_summaries.len() != 20;
+                    let mut summaries = data.match_summaries;
+                    summaries.iter_mut().for_each(|summary| {
+                        if let Entry::Vacant(entry) = self.tab.player_data.match_data_map.entry(summary.match_id) {
+                            entry.insert(None);
+                            self.send_message(Payload::GetMatchDetails { tab: self.tab.id, name: self.tab.riot_user_name.clone(), tag_line: self.tab.riot_tag_line.clone(), version: summary.version.clone(), id: summary.match_id, region_id: self.tab.region });
                         }
+
+                        self.load_items(ui::ddragon_version(versions, &summary.version), &summary.items);
+                    });
+                    self.load_champ_images(champs, summary_champions(&summaries));
+                    self.tab.player_data.match_summaries = Some(summaries)
+                }
+                Err(err) => {
+                    dbg!("{:?}", err);
+                }
+            },
+            Results::AllMatchSum(_, result) => match result {
+                Ok(summaries) => {
+                    self.load_champ_images(champs, summary_champions(&summaries));
+                    self.tab.player_data.all_match_summaries = Some(summaries);
+                }
+                Err(err) => {
+                    dbg!("{:?}", &err);
+                    self.tab.player_data.all_pages_started = false;
+                    self.tab.player_data.all_pages_error = Some(err.to_string());
+                }
+            },
+            Results::PlayerUpdate(_, update) => match update {
+                Ok(updated) => {
+                    let data = updated.data.update_player_profile;
+                    if data.success {
+                        // The stats over every page are out of date as well
+                        self.tab.player_data.reset_all_pages();
+                        self.update_matches();
+                    } else {
+                        dbg!("{:?}", data.error_reason);
                     }
-                    Err(err) => {
-                        dbg!("{:?}", err);
-                    }
-                },
-                Results::Ranking(ranking) => match ranking {
-                    Ok(ranking) => {
-                        self.player_data.ranking = ranking.data.overall_ranking;
-                    }
-                    Err(err) => {
-                        dbg!("{:?}", err);
-                    }
-                },
-                // Todo: Display this info
-                Results::PlayerInfo(info) => match info {
-                    Ok(data) => {
-                        let info = data.data.profile_init_simple.unwrap();
-                        let rank = data.data.fetch_profile_ranks;
-                        if info.player_info.riot_user_name.as_str() == self.riot_user_name.as_str()
-                            && info.player_info.riot_tag_line.as_str()
-                                == self.riot_tag_line.as_str()
-                        {
-                            self.player_data.icon_id = info.player_info.icon_id;
-                            let data: Option<Box<[_]>> = rank
-                                .map(|rank| {
-                                    rank.rank_scores.into_vec().into_iter().filter_map(|val| {
-                                        if val.queue_type.is_empty() {
-                                            None
-                                        } else {
-                                            Some(val)
-                                        }
-                                    }).collect()
-                                });
-                            self.player_data.rank_scores = data;
+                }
+                Err(err) => {
+                    dbg!("{:?}", err);
+                }
+            },
+            Results::Ranking(_, ranking) => match ranking {
+                Ok(ranking) => {
+                    self.tab.player_data.ranking = ranking.data.overall_ranking;
+                }
+                Err(err) => {
+                    dbg!("{:?}", err);
+                }
+            },
+            // Todo: Display this info
+            Results::PlayerInfo(request, region, info) => match info {
+                Ok(data) => {
+                    let rank = data.data.fetch_profile_ranks;
+                    let ranks: Option<Box<[_]>> = rank
+                        .map(|rank| {
+                            rank.rank_scores.into_vec().into_iter().filter_map(|val| {
+                                if val.queue_type.is_empty() {
+                                    None
+                                } else {
+                                    Some(val)
+                                }
+                            }).collect()
+                        });
+
+                    if let Some(info) = data.data.profile_init_simple {
+                        self.update_favorite(region, &info.player_info, ranks.as_deref());
+
+                        if request.is_some_and(|request| request.navigation == self.tab.navigation) {
+                            self.tab.player_data.icon_id = info.player_info.icon_id;
+                            self.tab.player_data.rank_scores = ranks;
                         }
                     }
-                    Err(err) => {
-                        dbg!("{:?}", err);
-                    }
-                },
-                Results::PlayerIcon(data) => {
-                    todo!("{:?}", data)
-                }
-                Results::ChampImage(image_errors) => {
-                    todo!("{:?}", image_errors)
-                }
-                Results::MatchDetails(result) => match result {
-                    Ok((match_details, id)) => {
-                        self.player_data
-                            .match_data_map
-                            .insert(id, Some(match_details.data.data_match));
-                    }
-                    Err(err) => {
-                        dbg!("{:?}", err);
+                }
+                Err(err) => {
+                    dbg!("{:?}", err);
+                }
+            },
+            // These already have a placeholder shown in their place
+            Results::PlayerIcon(err) | Results::ChampImage(err) | Results::ItemImage(err) => {
+                dbg!("{:?}", &err);
+                self.asset_error = Some(err.to_string());
+            }
+            Results::MatchDetails(_, result) => match result {
+                Ok((match_details, id)) => {
+                    let details = match_details.data.data_match;
+                    let summary = &details.match_summary;
+                    let players = || summary.team_a.iter().chain(summary.team_b.iter());
+
+                    let version = ui::ddragon_version(versions, &summary.version);
+                    for post_game in players().filter_map(|player| player.post_game.as_ref()) {
+                        self.load_items(version, post_game.items.as_deref().unwrap_or_default());
                     }
-                },
+                    self.load_champ_images(champs, players().map(|player| player.champion_id));
 
-                Results::ChampJson(err) => {
-                    todo!("{:?}", err)
+                    self.tab.player_data.match_data_map.insert(id, Some(details));
+                }
+                Err(err) => {
+                    dbg!("{:?}", err);
                 }
+            },
 
-                Results::PlayerSuggestions(result) => match result {
-                    Ok(suggestions) => self.player_suggestions = suggestions,
-                    Err(e) => todo!("{:?}", e),
-                },
+            Results::ChampJson(err) => {
+                dbg!("{:?}", err);
+            }
 
-                payload => unreachable!(
-                    "App has reached an impossible state, this should already be covered {:?}",
-                    payload
-                ),
+            Results::StaticData(err) => {
+                dbg!("{:?}", err);
             }
-        };
+
+            Results::ChampsRefreshed(result) => match result {
+                Ok(()) => {
+                    // The sheets and portraits of any new champs still need to be loaded
+                    self.data_dragon.sprites_started = false;
+                    if let (Some(champs), Some(summaries)) = (
+                        self.shared_state.champs(),
+                        self.tab.player_data.match_summaries.take(),
+                    ) {
+                        self.load_champ_images(&champs, summary_champions(&summaries));
+                        self.tab.player_data.match_summaries = Some(summaries);
+                    }
+                }
+                Err(err) => {
+                    dbg!("{:?}", err);
+                }
+            },
+
+            Results::PlayerSuggestions(result) => match result {
+                Ok(suggestions) => self.player_suggestions = suggestions,
+                Err(e) => todo!("{:?}", e),
+            },
+
+            payload => unreachable!(
+                "App has reached an impossible state, this should already be covered {:?}",
+                payload
+            ),
+        }
     }
 }